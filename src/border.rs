@@ -1,7 +1,7 @@
 use libccanvas::bindings::Colour;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct Border {
     pub colour: Colour,
@@ -9,7 +9,7 @@ pub struct Border {
     pub r#type: BorderType,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[serde(tag = "type")]
 pub enum BorderType {
@@ -110,6 +110,35 @@ impl BorderType {
             Self::Custom { bottomleft, .. } => *bottomleft,
         }
     }
+
+    /// character joining lines in the directions of `mask`, see `LineCanvas`
+    ///
+    /// custom borders have no junctions, so the straight line is used
+    pub fn junction(&self, mask: u8) -> char {
+        const NORMAL: [char; 16] = [
+            ' ', '╵', '╷', '│', '╴', '┘', '┐', '┤', '╶', '└', '┌', '├', '─', '┴', '┬', '┼',
+        ];
+        const ROUNDED: [char; 16] = [
+            ' ', '╵', '╷', '│', '╴', '╯', '╮', '┤', '╶', '╰', '╭', '├', '─', '┴', '┬', '┼',
+        ];
+        const THICK: [char; 16] = [
+            ' ', '╹', '╻', '┃', '╸', '┛', '┓', '┫', '╺', '┗', '┏', '┣', '━', '┻', '┳', '╋',
+        ];
+        const DOUBLE: [char; 16] = [
+            ' ', '║', '║', '║', '═', '╝', '╗', '╣', '═', '╚', '╔', '╠', '═', '╩', '╦', '╬',
+        ];
+
+        let mask = (mask & 0b1111) as usize;
+
+        match self {
+            Self::Normal => NORMAL[mask],
+            Self::Rounded => ROUNDED[mask],
+            Self::Thick => THICK[mask],
+            Self::Double => DOUBLE[mask],
+            Self::Custom { .. } if mask & 0b11 != 0 => self.left(),
+            Self::Custom { .. } => self.top(),
+        }
+    }
 }
 
 pub struct BorderSet {
@@ -137,3 +166,31 @@ impl From<&BorderType> for BorderSet {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DOWN, LEFT, RIGHT, UP};
+
+    #[test]
+    fn junctions_join_lines() {
+        assert_eq!(BorderType::Normal.junction(LEFT | RIGHT), '─');
+        assert_eq!(BorderType::Normal.junction(UP | DOWN | RIGHT), '├');
+        assert_eq!(BorderType::Rounded.junction(RIGHT | DOWN), '╭');
+        assert_eq!(BorderType::Thick.junction(UP | DOWN | LEFT | RIGHT), '╋');
+        assert_eq!(BorderType::Double.junction(LEFT | UP | DOWN), '╣');
+
+        let custom = BorderType::Custom {
+            left: 'l',
+            topleft: 'a',
+            top: 't',
+            topright: 'b',
+            right: 'r',
+            bottomright: 'c',
+            bottom: 'd',
+            bottomleft: 'e',
+        };
+        assert_eq!(custom.junction(UP | DOWN | RIGHT), 'l');
+        assert_eq!(custom.junction(LEFT | RIGHT), 't');
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use libccanvas::{bindings::Colour, client::Client, features::common::Rect};
+
+use crate::{Border, BorderSet, BorderType};
+
+/// directions a line leaves a cell in, combined as bits
+pub const UP: u8 = 1;
+pub const DOWN: u8 = 2;
+pub const LEFT: u8 = 4;
+pub const RIGHT: u8 = 8;
+
+enum Cell {
+    /// lines meeting in the cell, drawn as a single joined character
+    Line {
+        mask: u8,
+        r#type: BorderType,
+        colour: Colour,
+    },
+    /// characters of custom borders cannot be joined, so they overwrite
+    Char(char, Colour),
+}
+
+/// borders drawn by a layout, where lines of adjacent borders meet
+/// they are joined into junctions instead of overwriting each other
+#[derive(Default)]
+pub struct LineCanvas {
+    cells: HashMap<(u32, u32), Cell>,
+}
+
+impl LineCanvas {
+    /// draw a border on the edge of the rect
+    pub fn border(&mut self, rect: &Rect, border: &Border) {
+        if rect.width < 2 || rect.height < 2 {
+            return;
+        }
+
+        let right = rect.x + rect.width - 1;
+        let bottom = rect.y + rect.height - 1;
+        let borderset = BorderSet::from(&border.r#type);
+
+        self.line(rect.x, rect.y, RIGHT | DOWN, borderset.topleft, border);
+        self.line(right, rect.y, LEFT | DOWN, borderset.topright, border);
+        self.line(right, bottom, LEFT | UP, borderset.bottomright, border);
+        self.line(rect.x, bottom, RIGHT | UP, borderset.bottomleft, border);
+
+        (rect.x + 1..right).for_each(|x| {
+            self.line(x, rect.y, LEFT | RIGHT, borderset.top, border);
+            self.line(x, bottom, LEFT | RIGHT, borderset.bottom, border);
+        });
+
+        (rect.y + 1..bottom).for_each(|y| {
+            self.line(rect.x, y, UP | DOWN, borderset.left, border);
+            self.line(right, y, UP | DOWN, borderset.right, border);
+        });
+    }
+
+    /// add a line to the cell, `c` is only used by custom borders
+    fn line(&mut self, x: u32, y: u32, mask: u8, c: char, border: &Border) {
+        let cell = match &border.r#type {
+            BorderType::Custom { .. } => Cell::Char(c, border.colour),
+            r#type => Cell::Line {
+                mask: match self.cells.get(&(x, y)) {
+                    Some(Cell::Line { mask: existing, .. }) => existing | mask,
+                    _ => mask,
+                },
+                r#type: r#type.clone(),
+                colour: border.colour,
+            },
+        };
+
+        self.cells.insert((x, y), cell);
+    }
+
+    /// character and colour drawn in each cell
+    fn chars(&self) -> impl Iterator<Item = ((u32, u32), char, Colour)> + '_ {
+        self.cells.iter().map(|(at, cell)| match cell {
+            Cell::Line {
+                mask,
+                r#type,
+                colour,
+            } => (*at, r#type.junction(*mask), *colour),
+            Cell::Char(c, colour) => (*at, *c, *colour),
+        })
+    }
+
+    /// draw all cells to the screen
+    pub fn render(&self, client: &Client) {
+        self.chars()
+            .for_each(|((x, y), c, colour)| client.setcharcoloured(x, y, c, colour, Colour::Reset));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn border(r#type: BorderType) -> Border {
+        Border {
+            colour: Colour::Reset,
+            r#type,
+        }
+    }
+
+    fn char_at(canvas: &LineCanvas, x: u32, y: u32) -> Option<char> {
+        canvas
+            .chars()
+            .find(|(at, _, _)| *at == (x, y))
+            .map(|(_, c, _)| c)
+    }
+
+    #[test]
+    fn shared_edges_are_joined() {
+        let mut canvas = LineCanvas::default();
+        canvas.border(&Rect::new(0, 0, 3, 3), &border(BorderType::Normal));
+        canvas.border(&Rect::new(2, 0, 3, 3), &border(BorderType::Normal));
+
+        assert_eq!(char_at(&canvas, 0, 0), Some('┌'));
+        assert_eq!(char_at(&canvas, 2, 0), Some('┬'));
+        assert_eq!(char_at(&canvas, 2, 1), Some('│'));
+        assert_eq!(char_at(&canvas, 2, 2), Some('┴'));
+        assert_eq!(char_at(&canvas, 1, 1), None);
+
+        // four corners meeting in the middle
+        canvas.border(&Rect::new(0, 2, 3, 3), &border(BorderType::Normal));
+        canvas.border(&Rect::new(2, 2, 3, 3), &border(BorderType::Normal));
+        assert_eq!(char_at(&canvas, 2, 2), Some('┼'));
+        assert_eq!(char_at(&canvas, 0, 2), Some('├'));
+    }
+
+    #[test]
+    fn custom_borders_overwrite() {
+        let custom = BorderType::Custom {
+            left: 'l',
+            topleft: 'a',
+            top: 't',
+            topright: 'b',
+            right: 'r',
+            bottomright: 'c',
+            bottom: 'd',
+            bottomleft: 'e',
+        };
+        let mut canvas = LineCanvas::default();
+        canvas.border(&Rect::new(0, 0, 3, 3), &border(BorderType::Normal));
+        canvas.border(&Rect::new(2, 0, 3, 3), &border(custom));
+
+        assert_eq!(char_at(&canvas, 2, 0), Some('a'));
+        assert_eq!(char_at(&canvas, 2, 1), Some('l'));
+
+        // too small to have an inside, nothing is drawn
+        let mut canvas = LineCanvas::default();
+        canvas.border(&Rect::new(0, 0, 1, 3), &border(BorderType::Normal));
+        assert_eq!(canvas.chars().count(), 0);
+    }
+}
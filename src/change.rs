@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use libccanvas::bindings::Discriminator;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Area;
+
+/// difference between two allocations of areas to components
+#[derive(Serialize, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct LayoutChanged {
+    pub added: Vec<Area>,
+    pub removed: Vec<Discriminator>,
+    /// same size, different position
+    pub moved: Vec<Area>,
+    pub resized: Vec<Area>,
+}
+
+impl LayoutChanged {
+    /// compare the serialized rects allocated before and after a change
+    pub fn new(old: &HashMap<Discriminator, Value>, new: &HashMap<Discriminator, Value>) -> Self {
+        let mut changed = Self::default();
+
+        new.iter().for_each(|(component, rect)| {
+            let area = Area {
+                component: component.clone(),
+                rect: rect.clone(),
+            };
+
+            match old.get(component) {
+                None => changed.added.push(area),
+                Some(old) if old == rect => {}
+                Some(old) => {
+                    if old.get("width") == rect.get("width")
+                        && old.get("height") == rect.get("height")
+                    {
+                        changed.moved.push(area)
+                    } else {
+                        changed.resized.push(area)
+                    }
+                }
+            }
+        });
+
+        changed.removed = old
+            .keys()
+            .filter(|component| !new.contains_key(*component))
+            .cloned()
+            .collect();
+
+        changed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.resized.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libccanvas::features::common::Rect;
+
+    use super::*;
+
+    fn component(n: u32) -> Discriminator {
+        serde_json::from_value(serde_json::json!([n])).unwrap()
+    }
+
+    fn areas(rects: &[(u32, Rect)]) -> HashMap<Discriminator, Value> {
+        rects
+            .iter()
+            .map(|(n, rect)| (component(*n), serde_json::to_value(rect).unwrap()))
+            .collect()
+    }
+
+    fn components(areas: &[Area]) -> Vec<Discriminator> {
+        areas.iter().map(|area| area.component.clone()).collect()
+    }
+
+    #[test]
+    fn areas_are_sorted_by_change() {
+        let old = areas(&[
+            (1, Rect::new(0, 0, 10, 10)),
+            (2, Rect::new(10, 0, 10, 10)),
+            (3, Rect::new(20, 0, 10, 10)),
+            (4, Rect::new(30, 0, 10, 10)),
+        ]);
+        let new = areas(&[
+            (1, Rect::new(0, 0, 10, 10)),
+            (2, Rect::new(15, 0, 10, 10)),
+            (3, Rect::new(20, 0, 20, 10)),
+            (5, Rect::new(40, 0, 10, 10)),
+        ]);
+
+        let changed = LayoutChanged::new(&old, &new);
+        assert!(components(&changed.added) == vec![component(5)]);
+        assert!(changed.removed == vec![component(4)]);
+        assert!(components(&changed.moved) == vec![component(2)]);
+        assert!(components(&changed.resized) == vec![component(3)]);
+
+        assert!(LayoutChanged::new(&new, &new).is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+
+use libccanvas::bindings::Discriminator;
+use serde::Serialize;
+
+/// components that did not confirm a render in time
+#[derive(Serialize, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ConfirmFailed {
+    pub components: Vec<Discriminator>,
+    /// missed too many renders in a row, and were removed from the layout
+    pub dropped: Vec<Discriminator>,
+}
+
+impl ConfirmFailed {
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+/// counts the renders each component failed to confirm in a row
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Confirmations {
+    missed: HashMap<Discriminator, u32>,
+    /// components are dropped once they miss this many renders, never if 0
+    limit: u32,
+}
+
+impl Confirmations {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            missed: HashMap::new(),
+            limit,
+        }
+    }
+
+    /// record a render, `waited` are the components asked to confirm
+    /// and `unconfirmed` those that did not before the timeout
+    pub fn record(
+        &mut self,
+        waited: &HashSet<Discriminator>,
+        unconfirmed: &HashSet<Discriminator>,
+    ) -> ConfirmFailed {
+        let mut failed = ConfirmFailed::default();
+
+        waited.iter().for_each(|component| {
+            if !unconfirmed.contains(component) {
+                self.missed.remove(component);
+                return;
+            }
+
+            let missed = self.missed.entry(component.clone()).or_default();
+            *missed += 1;
+
+            if self.limit != 0 && *missed >= self.limit {
+                self.missed.remove(component);
+                failed.dropped.push(component.clone());
+            }
+
+            failed.components.push(component.clone());
+        });
+
+        failed
+    }
+
+    /// whether the component failed to confirm its last render
+    pub fn is_pending(&self, component: &Discriminator) -> bool {
+        self.missed.contains_key(component)
+    }
+
+    /// forget components that are no longer in the layout
+    pub fn retain(&mut self, f: impl Fn(&Discriminator) -> bool) {
+        self.missed.retain(|component, _| f(component));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(n: u32) -> Discriminator {
+        serde_json::from_value(serde_json::json!([n])).unwrap()
+    }
+
+    fn set(components: &[u32]) -> HashSet<Discriminator> {
+        components.iter().copied().map(component).collect()
+    }
+
+    #[test]
+    fn dropped_after_missing_in_a_row() {
+        let mut confirmations = Confirmations::new(2);
+
+        let failed = confirmations.record(&set(&[1, 2]), &set(&[1]));
+        assert!(failed.components == vec![component(1)]);
+        assert!(failed.dropped.is_empty());
+        assert!(confirmations.is_pending(&component(1)));
+
+        // confirming resets the count
+        assert!(confirmations.record(&set(&[1]), &set(&[])).is_empty());
+        assert!(!confirmations.is_pending(&component(1)));
+        confirmations.record(&set(&[1]), &set(&[1]));
+
+        let failed = confirmations.record(&set(&[1]), &set(&[1]));
+        assert!(failed.dropped == vec![component(1)]);
+        assert!(!confirmations.is_pending(&component(1)));
+    }
+
+    #[test]
+    fn never_dropped_without_a_limit() {
+        let mut confirmations = Confirmations::new(0);
+
+        (0..10).for_each(|_| {
+            let failed = confirmations.record(&set(&[1]), &set(&[1]));
+            assert!(failed.components == vec![component(1)]);
+            assert!(failed.dropped.is_empty());
+        });
+    }
+}
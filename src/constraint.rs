@@ -1,10 +1,20 @@
-use serde::Deserialize;
+use std::{fmt, str::FromStr};
 
-#[derive(Deserialize)]
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+/// deserializes from either the tagged object form, or an expression string
+/// such as `"50% - 5"`, `"min(30%, 40)"` or `"fill*2"`, see `Constraint::from_str`,
+/// always serializes to the tagged object form
+#[derive(Serialize, Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct Constraint {
     base: ConstraintVariant,
+    #[serde(skip_serializing_if = "Option::is_none")]
     offset_pos: Option<Box<Constraint>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     offset_neg: Option<Box<Constraint>>,
 }
 
@@ -21,8 +31,39 @@ impl Constraint {
         }
     }
 
+    pub fn base(&self) -> &ConstraintVariant {
+        &self.base
+    }
+
+    /// the value of a length without offsets
+    fn plain_length(&self) -> Option<u32> {
+        match self.base {
+            ConstraintVariant::Length { value }
+                if self.offset_pos.is_none() && self.offset_neg.is_none() =>
+            {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// weight of the constraint if it shares leftover space in a split
+    ///
+    /// offsets are ignored for such constraints
+    pub fn fill_weight(&self) -> Option<u32> {
+        match self.base {
+            ConstraintVariant::Fill { weight } => Some(weight),
+            _ => None,
+        }
+    }
+
     pub fn eval(&self, length: u32) -> u32 {
-        let base = self.base.eval(length);
+        self.eval_from(self.base.eval(length))
+    }
+
+    /// apply the offsets to an already evaluated base,
+    /// such as a clamped length or the preferred size of an auto constraint
+    pub fn eval_from(&self, base: u32) -> u32 {
         (base
             + self
                 .offset_pos
@@ -38,7 +79,288 @@ impl Constraint {
     }
 }
 
+impl From<ConstraintVariant> for Constraint {
+    fn from(value: ConstraintVariant) -> Self {
+        Self::new(value, None, None)
+    }
+}
+
+/// the object form of a constraint
 #[derive(Deserialize)]
+struct TaggedConstraint {
+    base: ConstraintVariant,
+    offset_pos: Option<Box<Constraint>>,
+    offset_neg: Option<Box<Constraint>>,
+}
+
+impl<'de> Deserialize<'de> for Constraint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ConstraintVisitor;
+
+        impl<'de> Visitor<'de> for ConstraintVisitor {
+            type Value = Constraint;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a constraint object or expression")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let tagged = TaggedConstraint::deserialize(MapAccessDeserializer::new(map))?;
+
+                Ok(Constraint {
+                    base: tagged.base,
+                    offset_pos: tagged.offset_pos,
+                    offset_neg: tagged.offset_neg,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(ConstraintVisitor)
+    }
+}
+
+/// error in a constraint expression
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ParseError {
+    /// position of the error, starting from 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = ParseError;
+
+    /// parse a constraint expression
+    ///
+    /// - `40` length
+    /// - `50%` percentage
+    /// - `1/3` ratio
+    /// - `fill`, `fill*2` fill with a weight
+    /// - `auto` the preferred size of the component
+    /// - `min(30%, 80)`, `max(30%, 20)` clamp with an upper or lower bound
+    /// - `clamp(20, 30%, 80)` clamp with both bounds
+    ///
+    /// followed by at most one `+ offset` and one `- offset`, where offsets are
+    /// evaluated against the base, as with the object form
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.chars().collect(),
+            pos: 0,
+        };
+
+        let constraint = parser.expr()?;
+
+        match parser.peek() {
+            Some(c) => Err(parser.error(format!("unexpected '{c}'"))),
+            None => Ok(constraint),
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            column: self.pos + 1,
+            message,
+        }
+    }
+
+    /// next character that is not whitespace
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected '{expected}', found '{c}'"))),
+            None => Err(self.error(format!("expected '{expected}', found end of input"))),
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        self.peek();
+        let start = self.pos;
+
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            return Err(self.error("expected a number".to_string()));
+        }
+
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| ParseError {
+                column: start + 1,
+                message: "number is too large".to_string(),
+            })
+    }
+
+    /// base followed by offsets
+    fn expr(&mut self) -> Result<Constraint, ParseError> {
+        let base = self.term()?;
+        let mut offset_pos = None;
+        let mut offset_neg = None;
+
+        while let Some(sign @ ('+' | '-')) = self.peek() {
+            let column = self.pos + 1;
+            self.pos += 1;
+
+            let offset = if sign == '+' {
+                &mut offset_pos
+            } else {
+                &mut offset_neg
+            };
+
+            if offset.is_some() {
+                return Err(ParseError {
+                    column,
+                    message: format!("only one '{sign}' offset is allowed"),
+                });
+            }
+
+            *offset = Some(Constraint::from(self.term()?));
+        }
+
+        Ok(Constraint::new(base, offset_pos, offset_neg))
+    }
+
+    fn term(&mut self) -> Result<ConstraintVariant, ParseError> {
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() => {
+                let value = self.number()?;
+
+                match self.peek() {
+                    Some('%') => {
+                        self.pos += 1;
+                        Ok(ConstraintVariant::percentage(value))
+                    }
+                    Some('/') => {
+                        self.pos += 1;
+                        self.peek();
+                        let column = self.pos + 1;
+
+                        match self.number()? {
+                            0 => Err(ParseError {
+                                column,
+                                message: "ratio cannot have a denominator of 0".to_string(),
+                            }),
+                            den => Ok(ConstraintVariant::ratio(value, den)),
+                        }
+                    }
+                    _ => Ok(ConstraintVariant::length(value)),
+                }
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let start = self.pos;
+
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_alphabetic())
+                {
+                    self.pos += 1;
+                }
+
+                let name: String = self.chars[start..self.pos].iter().collect();
+
+                match name.as_str() {
+                    "fill" => {
+                        if self.peek() == Some('*') {
+                            self.pos += 1;
+                            Ok(ConstraintVariant::fill(self.number()?))
+                        } else {
+                            Ok(ConstraintVariant::fill(1))
+                        }
+                    }
+                    "auto" => Ok(ConstraintVariant::Auto),
+                    "min" | "max" => {
+                        self.expect('(')?;
+                        let a = self.expr()?;
+                        self.expect(',')?;
+                        let b = self.expr()?;
+                        self.expect(')')?;
+
+                        // one side is the bound, the other is what is bounded
+                        let (base, bound) = match (a.plain_length(), b.plain_length()) {
+                            (_, Some(bound)) => (a, bound),
+                            (Some(bound), None) => (b, bound),
+                            (None, None) => {
+                                return Err(ParseError {
+                                    column: start + 1,
+                                    message: format!(
+                                        "{name}() needs a plain number as one of its arguments"
+                                    ),
+                                })
+                            }
+                        };
+
+                        Ok(if name == "min" {
+                            ConstraintVariant::clamp(base, None, Some(bound))
+                        } else {
+                            ConstraintVariant::clamp(base, Some(bound), None)
+                        })
+                    }
+                    "clamp" => {
+                        self.expect('(')?;
+                        let min = self.number()?;
+                        self.expect(',')?;
+                        let base = self.expr()?;
+                        self.expect(',')?;
+                        let max = self.number()?;
+                        self.expect(')')?;
+
+                        Ok(ConstraintVariant::clamp(base, Some(min), Some(max)))
+                    }
+                    _ => Err(ParseError {
+                        column: start + 1,
+                        message: format!("unknown name '{name}'"),
+                    }),
+                }
+            }
+            Some(c) => Err(self.error(format!("unexpected '{c}'"))),
+            None => Err(self.error("unexpected end of input".to_string())),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[serde(tag = "type")]
 pub enum ConstraintVariant {
@@ -50,6 +372,32 @@ pub enum ConstraintVariant {
     Length { value: u32 },
     #[serde(rename = "percentage")]
     Percentage { value: u32 },
+    /// `num / den` of the length, without rounding to a whole percentage
+    #[serde(rename = "ratio")]
+    Ratio { num: u32, den: u32 },
+    /// the base constraint kept within the bounds, shrinking to the available length
+    /// rather than collapsing to 0 if it is smaller than the lower bound
+    #[serde(rename = "clamp")]
+    Clamp {
+        base: Box<Constraint>,
+        min: Option<u32>,
+        max: Option<u32>,
+    },
+    /// whatever is left after all other children of a split,
+    /// shared between all fills by weight
+    #[serde(rename = "fill")]
+    Fill {
+        #[serde(default = "default_weight")]
+        weight: u32,
+    },
+    /// the preferred size the component reports in its size hint,
+    /// a fill until it reports one
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+fn default_weight() -> u32 {
+    1
 }
 
 impl ConstraintVariant {
@@ -69,6 +417,26 @@ impl ConstraintVariant {
         Self::Percentage { value }
     }
 
+    pub fn ratio(num: u32, den: u32) -> Self {
+        Self::Ratio { num, den }
+    }
+
+    pub fn clamp(base: Constraint, min: Option<u32>, max: Option<u32>) -> Self {
+        Self::Clamp {
+            base: Box::new(base),
+            min,
+            max,
+        }
+    }
+
+    pub fn fill(weight: u32) -> Self {
+        Self::Fill { weight }
+    }
+
+    pub fn auto() -> Self {
+        Self::Auto
+    }
+
     pub fn eval(&self, length: u32) -> u32 {
         match self {
             Self::Max { value } => length.min(*value),
@@ -79,6 +447,71 @@ impl ConstraintVariant {
             Self::Percentage { value } => {
                 (((length * value) as f32 / 100_f32).round() as u32).min(length)
             }
+            Self::Ratio { den: 0, .. } => 0,
+            Self::Ratio { num, den } => {
+                (((length as u64 * *num as u64) as f64 / *den as f64).round() as u32).min(length)
+            }
+            Self::Clamp { base, min, max } => base
+                .eval(length)
+                .max(min.unwrap_or(0))
+                .min(max.unwrap_or(u32::MAX))
+                .min(length),
+            // outside of a split, there is nothing to share with
+            Self::Fill { .. } | Self::Auto => length,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Constraint {
+        s.parse().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn column(s: &str) -> usize {
+        match s.parse::<Constraint>() {
+            Ok(_) => panic!("{s:?} should not parse"),
+            Err(err) => err.column,
+        }
+    }
+
+    #[test]
+    fn parses_expressions() {
+        assert_eq!(parse("40").eval(100), 40);
+        assert_eq!(parse("50%").eval(80), 40);
+        assert_eq!(parse("50% - 5").eval(100), 45);
+        assert_eq!(parse("1/3").eval(90), 30);
+        assert_eq!(parse("min(30%, 80)").eval(100), 30);
+        assert_eq!(parse("max(30%, 20)").eval(50), 20);
+        assert_eq!(parse("clamp(20, 30%, 80)").eval(40), 20);
+        assert_eq!(parse("clamp(20, 30%, 80) + 5").eval(100), 35);
+        assert_eq!(parse("fill*2").fill_weight(), Some(2));
+        assert!(matches!(parse(" auto ").base(), ConstraintVariant::Auto));
+    }
+
+    #[test]
+    fn reports_error_columns() {
+        assert_eq!(column(""), 1);
+        assert_eq!(column("fil"), 1);
+        assert_eq!(column("12x"), 3);
+        assert_eq!(column("1/0"), 3);
+        assert_eq!(column("50% +"), 6);
+        assert_eq!(column("50 + 1 + 2"), 8);
+        assert_eq!(column("min(30%, 80"), 12);
+        assert_eq!(column("99999999999"), 1);
+    }
+
+    #[test]
+    fn deserializes_both_forms() {
+        let parsed: Constraint = serde_json::from_str(r#""50% - 5""#).unwrap();
+        let tagged: Constraint = serde_json::from_str(
+            r#"{ "base": { "type": "percentage", "value": 50 },
+                 "offset_neg": { "base": { "type": "length", "value": 5 } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.eval(100), tagged.eval(100));
+    }
+}
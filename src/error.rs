@@ -0,0 +1,69 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// why a request could not be applied
+// always debug, as required by `std::error::Error`
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type")]
+pub enum LayoutError {
+    /// the message is not a valid request
+    #[serde(rename = "invalid json")]
+    InvalidJson { message: String },
+    /// no node, component or window matches the target
+    #[serde(rename = "not found")]
+    NotFound,
+    /// the path steps in a direction the split does not have,
+    /// or there is no split in the orientation to resize
+    #[serde(rename = "wrong split direction")]
+    WrongSplitDirection,
+    /// the node does not support the operation, such as switching tabs of a split
+    #[serde(rename = "wrong node type")]
+    WrongNodeType,
+    /// one node contains the other
+    #[serde(rename = "overlapping")]
+    Overlapping,
+    /// there is not enough space to resize without going below the minimum
+    #[serde(rename = "no space")]
+    NoSpace,
+    /// the request needs a tiling algorithm, but none is set
+    #[serde(rename = "tiling disabled")]
+    TilingDisabled,
+    /// the layout is generated by a tiling algorithm and cannot be edited directly
+    #[serde(rename = "tiling enabled")]
+    TilingEnabled,
+    /// another node in the layout already has the id
+    #[serde(rename = "duplicate id")]
+    DuplicateId { id: String },
+    /// queries are answered on their own, and cannot be part of a batch
+    #[serde(rename = "query in batch")]
+    QueryInBatch,
+    /// the request at `index` of a batch failed, so none of the batch is applied
+    #[serde(rename = "batch failed")]
+    BatchFailed {
+        index: usize,
+        error: Box<LayoutError>,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson { message } => write!(f, "invalid request: {message}"),
+            Self::NotFound => f.write_str("target not found"),
+            Self::WrongSplitDirection => f.write_str("wrong split direction"),
+            Self::WrongNodeType => f.write_str("node does not support the operation"),
+            Self::Overlapping => f.write_str("one node contains the other"),
+            Self::NoSpace => f.write_str("not enough space"),
+            Self::TilingDisabled => f.write_str("no tiling algorithm is set"),
+            Self::TilingEnabled => f.write_str("the layout is generated by a tiling algorithm"),
+            Self::DuplicateId { id } => write!(f, "id {id} is already in use"),
+            Self::QueryInBatch => f.write_str("queries cannot be batched"),
+            Self::BatchFailed { index, error } => {
+                write!(f, "request {index} of the batch failed: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
@@ -0,0 +1,236 @@
+use libccanvas::{
+    bindings::{Colour, Discriminator},
+    client::Client,
+    features::common::Rect,
+};
+use serde::Deserialize;
+
+use crate::{Constraint, Layout, LayoutError, SizeHints};
+
+/// a layout floating above the tiled layout
+#[derive(Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Floating {
+    pub id: String,
+    pub layout: Layout,
+    pub width: Constraint,
+    pub height: Constraint,
+    /// offset from the left of the screen, centred if none
+    pub x: Option<Constraint>,
+    /// offset from the top of the screen, centred if none
+    pub y: Option<Constraint>,
+}
+
+impl Floating {
+    pub fn new(
+        id: String,
+        layout: Layout,
+        width: Constraint,
+        height: Constraint,
+        x: Option<Constraint>,
+        y: Option<Constraint>,
+    ) -> Self {
+        Self {
+            id,
+            layout,
+            width,
+            height,
+            x,
+            y,
+        }
+    }
+
+    /// area taken up by the window, always kept within the screen
+    pub fn rect(&self, screen: &Rect) -> Rect {
+        let width = self.width.eval(screen.width).min(screen.width);
+        let height = self.height.eval(screen.height).min(screen.height);
+
+        let x = match &self.x {
+            Some(x) => x.eval(screen.width).min(screen.width - width),
+            None => (screen.width - width) / 2,
+        };
+        let y = match &self.y {
+            Some(y) => y.eval(screen.height).min(screen.height - height),
+            None => (screen.height - height) / 2,
+        };
+
+        Rect::new(screen.x + x, screen.y + y, width, height)
+    }
+}
+
+/// floating windows, ordered from bottom to top
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct FloatingLayer {
+    windows: Vec<Floating>,
+}
+
+impl FloatingLayer {
+    /// add a window on top of all others, replacing any window with the same id
+    pub fn add(&mut self, window: Floating) {
+        self.close(&window.id);
+        self.windows.push(window);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Floating> {
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Option<&mut Floating> {
+        self.windows.iter_mut().find(|window| window.id == id)
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.windows.iter().position(|window| window.id == id)
+    }
+
+    /// move a window
+    pub fn move_to(
+        &mut self,
+        id: &str,
+        x: Option<Constraint>,
+        y: Option<Constraint>,
+    ) -> Result<(), LayoutError> {
+        let window = self.get_mut(id).ok_or(LayoutError::NotFound)?;
+        window.x = x;
+        window.y = y;
+        Ok(())
+    }
+
+    /// resize a window
+    pub fn resize(
+        &mut self,
+        id: &str,
+        width: Constraint,
+        height: Constraint,
+    ) -> Result<(), LayoutError> {
+        let window = self.get_mut(id).ok_or(LayoutError::NotFound)?;
+        window.width = width;
+        window.height = height;
+        Ok(())
+    }
+
+    /// remove a window, returning it if it exists
+    pub fn close(&mut self, id: &str) -> Option<Floating> {
+        self.position(id).map(|index| self.windows.remove(index))
+    }
+
+    /// bring a window above all others
+    pub fn raise(&mut self, id: &str) -> Result<(), LayoutError> {
+        let window = self.close(id).ok_or(LayoutError::NotFound)?;
+        self.windows.push(window);
+        Ok(())
+    }
+
+    /// send a window below all others
+    pub fn lower(&mut self, id: &str) -> Result<(), LayoutError> {
+        let window = self.close(id).ok_or(LayoutError::NotFound)?;
+        self.windows.insert(0, window);
+        Ok(())
+    }
+
+    /// remove the component from all windows, windows left empty are closed
+    pub fn remove_component(&mut self, component: &Discriminator, screen: Rect, hints: &SizeHints) {
+        self.windows.retain_mut(|window| {
+            let rect = window.rect(&screen);
+            !window.layout.remove_component(component, rect, hints)
+                || !matches!(window.layout, Layout::None)
+        });
+    }
+
+    pub fn components(&self) -> Vec<Discriminator> {
+        self.windows
+            .iter()
+            .flat_map(|window| window.layout.components())
+            .collect()
+    }
+
+    /// draw windows from bottom to top, each window is cleared
+    /// before drawing so it covers whatever is below it
+    pub fn areas(
+        &self,
+        screen: Rect,
+        client: &Client,
+        hints: &SizeHints,
+    ) -> Vec<(Rect, Discriminator)> {
+        let mut areas = Vec::new();
+
+        self.windows.iter().for_each(|window| {
+            let rect = window.rect(&screen);
+
+            (rect.y..rect.y + rect.height).for_each(|y| {
+                (rect.x..rect.x + rect.width)
+                    .for_each(|x| client.setcharcoloured(x, y, ' ', Colour::Reset, Colour::Reset))
+            });
+
+            areas.extend(window.layout.areas(rect, client, hints));
+        });
+
+        areas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstraintVariant;
+
+    fn component(n: u32) -> Discriminator {
+        serde_json::from_value(serde_json::json!([n])).unwrap()
+    }
+
+    fn length(value: u32) -> Constraint {
+        Constraint::new(ConstraintVariant::length(value), None, None)
+    }
+
+    fn window(id: &str, n: u32) -> Floating {
+        Floating::new(
+            id.to_string(),
+            Layout::single(Some(component(n)), None),
+            length(10),
+            length(4),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn windows_are_ordered_bottom_to_top() {
+        let mut layer = FloatingLayer::default();
+        layer.add(window("a", 1));
+        layer.add(window("b", 2));
+        layer.add(window("c", 3));
+        assert!(layer.components() == vec![component(1), component(2), component(3)]);
+
+        assert!(layer.raise("a").is_ok());
+        assert!(layer.components() == vec![component(2), component(3), component(1)]);
+
+        assert!(layer.lower("c").is_ok());
+        assert!(layer.components() == vec![component(3), component(2), component(1)]);
+
+        // adding a window with a used id replaces it, on top
+        layer.add(window("c", 4));
+        assert!(layer.components() == vec![component(2), component(1), component(4)]);
+
+        assert!(layer.raise("d") == Err(LayoutError::NotFound));
+        assert!(layer.close("b").is_some());
+        assert!(layer.get("b").is_none());
+    }
+
+    #[test]
+    fn windows_are_kept_on_screen() {
+        let screen = Rect::new(2, 1, 40, 20);
+
+        // centred if no position is given
+        assert!(window("a", 1).rect(&screen) == Rect::new(17, 9, 10, 4));
+
+        let mut moved = window("a", 1);
+        moved.x = Some(length(35));
+        moved.y = Some(length(3));
+        assert!(moved.rect(&screen) == Rect::new(32, 4, 10, 4));
+
+        let mut large = window("a", 1);
+        large.width = Constraint::new(ConstraintVariant::percentage(150), None, None);
+        assert!(large.rect(&screen) == Rect::new(2, 9, 40, 4));
+    }
+}
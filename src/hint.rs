@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use libccanvas::bindings::Discriminator;
+use serde::Deserialize;
+
+use crate::Orientation;
+
+/// sizes a component reports about itself, in cells
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SizeHint {
+    /// below this the component is unusable
+    #[serde(default)]
+    pub min_width: u32,
+    #[serde(default)]
+    pub min_height: u32,
+    pub preferred_width: Option<u32>,
+    pub preferred_height: Option<u32>,
+}
+
+impl SizeHint {
+    /// minimum length along the orientation
+    pub fn min(&self, orientation: Orientation) -> u32 {
+        match orientation {
+            Orientation::Horizontal => self.min_width,
+            Orientation::Vertical => self.min_height,
+        }
+    }
+
+    /// preferred length along the orientation, if reported
+    pub fn preferred(&self, orientation: Orientation) -> Option<u32> {
+        match orientation {
+            Orientation::Horizontal => self.preferred_width,
+            Orientation::Vertical => self.preferred_height,
+        }
+    }
+}
+
+/// size of a node along one orientation, from the hints of the components in it
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct HintedSize {
+    pub min: u32,
+    pub preferred: Option<u32>,
+}
+
+/// latest size hint of each component
+pub type SizeHints = HashMap<Discriminator, SizeHint>;
@@ -1,48 +1,338 @@
+use std::collections::{HashMap, HashSet};
+
 use libccanvas::{
     bindings::{Colour, Discriminator},
     client::Client,
     features::common::{Direction, Rect},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    solve_with_hints, Border, BorderSet, BorderType, Constraint, ConstraintVariant, HintedSize,
+    LayoutError, LineCanvas, SizeHints, Step, Target, Unsatisfiable,
+};
 
-use crate::{Border, BorderSet, Constraint};
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum Orientation {
+    /// children are placed left to right
+    #[serde(rename = "horizontal")]
+    Horizontal,
+    /// children are placed top to bottom
+    #[serde(rename = "vertical")]
+    Vertical,
+}
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[serde(tag = "type")]
 pub enum Layout {
+    #[default]
     #[serde(rename = "none")]
     None,
     #[serde(rename = "single")]
     Single {
+        id: Option<String>,
         discrim: Option<Discriminator>,
         border: Option<Border>,
+        /// keep the area given to the component at this ratio, centred in the pane
+        aspect_ratio: Option<AspectRatio>,
+        /// empty cells between the border and the component
+        #[serde(default)]
+        padding: u32,
     },
     #[serde(rename = "split horizontal")]
     SplitHorizontal {
+        id: Option<String>,
         left_constraint: Constraint,
         left: Box<Layout>,
         right_constraint: Constraint,
         right: Box<Layout>,
+        /// empty cells between the children
+        #[serde(default)]
+        gap: u32,
+        /// empty cells around the children
+        #[serde(default)]
+        padding: u32,
+        /// children share the borders between them, drawn with junctions where they meet
+        #[serde(default)]
+        collapse: bool,
     },
     #[serde(rename = "split vertical")]
     SplitVertical {
+        id: Option<String>,
         top_constraint: Constraint,
         top: Box<Layout>,
         bottom_constraint: Constraint,
         bottom: Box<Layout>,
+        #[serde(default)]
+        gap: u32,
+        #[serde(default)]
+        padding: u32,
+        #[serde(default)]
+        collapse: bool,
+    },
+    /// split with any number of children, addressed by index
+    #[serde(rename = "split")]
+    Split {
+        id: Option<String>,
+        orientation: Orientation,
+        children: Vec<(Constraint, Layout)>,
+        #[serde(default)]
+        gap: u32,
+        #[serde(default)]
+        padding: u32,
+        #[serde(default)]
+        collapse: bool,
+    },
+    /// children stacked in the same area, only the active tab is shown
+    #[serde(rename = "tabs")]
+    Tabs {
+        id: Option<String>,
+        #[serde(default)]
+        active: usize,
+        tabs: Vec<Tab>,
+        border: Option<Border>,
     },
+    /// cells on rows and columns, a cell may span several rows or columns
+    #[serde(rename = "grid")]
+    Grid {
+        id: Option<String>,
+        rows: Vec<Constraint>,
+        columns: Vec<Constraint>,
+        cells: Vec<GridCell>,
+        /// empty cells between rows and between columns
+        #[serde(default)]
+        gap: u32,
+        #[serde(default)]
+        padding: u32,
+        #[serde(default)]
+        collapse: bool,
+    },
+    /// alternative layouts, the first one matching the available area is shown
+    #[serde(rename = "responsive")]
+    Responsive {
+        id: Option<String>,
+        alternatives: Vec<Breakpoint>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Tab {
+    #[serde(default)]
+    pub title: String,
+    pub layout: Layout,
+}
+
+impl Tab {
+    pub fn new(title: String, layout: Layout) -> Self {
+        Self { title, layout }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct GridCell {
+    pub row: usize,
+    pub column: usize,
+    #[serde(default = "default_span")]
+    pub row_span: usize,
+    #[serde(default = "default_span")]
+    pub column_span: usize,
+    pub layout: Layout,
+}
+
+fn default_span() -> usize {
+    1
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ScreenOrientation {
+    /// cells are about twice as tall as they are wide, so an area is
+    /// landscape if it is at least twice as many columns as rows
+    #[serde(rename = "landscape")]
+    Landscape,
+    #[serde(rename = "portrait")]
+    Portrait,
+}
+
+/// a layout shown if the available area meets all conditions
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Breakpoint {
+    pub min_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub orientation: Option<ScreenOrientation>,
+    pub layout: Layout,
+}
+
+impl Breakpoint {
+    pub fn new(
+        min_width: Option<u32>,
+        min_height: Option<u32>,
+        orientation: Option<ScreenOrientation>,
+        layout: Layout,
+    ) -> Self {
+        Self {
+            min_width,
+            min_height,
+            orientation,
+            layout,
+        }
+    }
+
+    pub fn matches(&self, rect: &Rect) -> bool {
+        let orientation = if rect.width >= rect.height * 2 {
+            ScreenOrientation::Landscape
+        } else {
+            ScreenOrientation::Portrait
+        };
+
+        self.min_width.is_none_or(|width| rect.width >= width)
+            && self.min_height.is_none_or(|height| rect.height >= height)
+            && self.orientation.is_none_or(|wanted| wanted == orientation)
+    }
+}
+
+impl GridCell {
+    /// first row (vertical) or column (horizontal) the cell is in, and the one after its last,
+    /// spans past the last of `count` are cut off
+    fn tracks(&self, orientation: Orientation, count: usize) -> (usize, usize) {
+        let (start, span) = match orientation {
+            Orientation::Horizontal => (self.column, self.column_span),
+            Orientation::Vertical => (self.row, self.row_span),
+        };
+
+        (start, (start + span.max(1)).min(count))
+    }
+
+    pub fn new(
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+        layout: Layout,
+    ) -> Self {
+        Self {
+            row,
+            column,
+            row_span,
+            column_span,
+            layout,
+        }
+    }
+}
+
+/// width to height ratio in cells
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct AspectRatio {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AspectRatio {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// largest rect with the ratio that fits in `rect`, centred in it
+    pub fn letterbox(&self, rect: Rect) -> Rect {
+        if self.width == 0 || self.height == 0 {
+            return rect;
+        }
+
+        let (width, height) =
+            if rect.width as u64 * self.height as u64 > rect.height as u64 * self.width as u64 {
+                (
+                    (rect.height as u64 * self.width as u64 / self.height as u64) as u32,
+                    rect.height,
+                )
+            } else {
+                (
+                    rect.width,
+                    (rect.width as u64 * self.height as u64 / self.width as u64) as u32,
+                )
+            };
+
+        Rect::new(
+            rect.x + (rect.width - width) / 2,
+            rect.y + (rect.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+impl Orientation {
+    /// orientation of a split placing nodes in the direction
+    fn of(direction: &Direction) -> Self {
+        match direction {
+            Direction::Left | Direction::Right => Self::Horizontal,
+            Direction::Up | Direction::Down => Self::Vertical,
+        }
+    }
+
+    /// length of the rect along the orientation
+    fn length(&self, rect: &Rect) -> u32 {
+        match self {
+            Self::Horizontal => rect.width,
+            Self::Vertical => rect.height,
+        }
+    }
+
+    /// part of the rect starting at `offset` along the orientation
+    fn slice(&self, rect: &Rect, offset: u32, length: u32) -> Rect {
+        match self {
+            Self::Horizontal => Rect::new(rect.x + offset, rect.y, length, rect.height),
+            Self::Vertical => Rect::new(rect.x, rect.y + offset, rect.width, length),
+        }
+    }
+}
+
+/// unit of a size given in a request
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum Unit {
+    #[default]
+    #[serde(rename = "cells")]
+    Cells,
+    /// percentage of the parent split
+    #[serde(rename = "percent")]
+    Percent,
+}
+
+/// how a node is resized
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ResizeOptions {
+    pub orientation: Orientation,
+    pub unit: Unit,
+    /// smallest size in cells either pane can shrink to
+    pub min: u32,
 }
 
-impl Default for Layout {
-    fn default() -> Self {
-        Self::None
+impl ResizeOptions {
+    pub fn new(orientation: Orientation, unit: Unit, min: u32) -> Self {
+        Self {
+            orientation,
+            unit,
+            min,
+        }
     }
 }
 
 impl Layout {
     pub fn single(discrim: Option<Discriminator>, border: Option<Border>) -> Self {
-        Self::Single { discrim, border }
+        Self::Single {
+            id: None,
+            discrim,
+            border,
+            aspect_ratio: None,
+            padding: 0,
+        }
     }
 
     pub fn horizontal(
@@ -52,10 +342,14 @@ impl Layout {
         right_constraint: Constraint,
     ) -> Self {
         Self::SplitHorizontal {
+            id: None,
             left: left.into(),
             right: right.into(),
             left_constraint,
             right_constraint,
+            gap: 0,
+            padding: 0,
+            collapse: false,
         }
     }
 
@@ -66,372 +360,1610 @@ impl Layout {
         bottom_constraint: Constraint,
     ) -> Self {
         Self::SplitVertical {
+            id: None,
             top: top.into(),
             bottom: bottom.into(),
             top_constraint,
             bottom_constraint,
+            gap: 0,
+            padding: 0,
+            collapse: false,
+        }
+    }
+
+    pub fn split(orientation: Orientation, children: Vec<(Constraint, Layout)>) -> Self {
+        Self::Split {
+            id: None,
+            orientation,
+            children,
+            gap: 0,
+            padding: 0,
+            collapse: false,
+        }
+    }
+
+    pub fn tabs(tabs: Vec<Tab>, border: Option<Border>) -> Self {
+        Self::Tabs {
+            id: None,
+            active: 0,
+            tabs,
+            border,
         }
     }
+
+    pub fn grid(rows: Vec<Constraint>, columns: Vec<Constraint>, cells: Vec<GridCell>) -> Self {
+        Self::Grid {
+            id: None,
+            rows,
+            columns,
+            cells,
+            gap: 0,
+            padding: 0,
+            collapse: false,
+        }
+    }
+
+    /// give the node an id so it can be addressed regardless of its position
+    pub fn with_id(mut self, id: String) -> Self {
+        self.set_id(Some(id));
+        self
+    }
+
+    /// leave empty cells between the children of a split or grid
+    pub fn with_gap(mut self, new: u32) -> Self {
+        match &mut self {
+            Self::SplitHorizontal { gap, .. }
+            | Self::SplitVertical { gap, .. }
+            | Self::Split { gap, .. }
+            | Self::Grid { gap, .. } => *gap = new,
+            _ => {}
+        }
+        self
+    }
+
+    /// let the children of a split or grid share the borders between them
+    pub fn with_collapse(mut self, new: bool) -> Self {
+        match &mut self {
+            Self::SplitHorizontal { collapse, .. }
+            | Self::SplitVertical { collapse, .. }
+            | Self::Split { collapse, .. }
+            | Self::Grid { collapse, .. } => *collapse = new,
+            _ => {}
+        }
+        self
+    }
+
+    /// leave empty cells around the children of a split or grid,
+    /// or between the border and the component of a single pane
+    pub fn with_padding(mut self, new: u32) -> Self {
+        match &mut self {
+            Self::Single { padding, .. }
+            | Self::SplitHorizontal { padding, .. }
+            | Self::SplitVertical { padding, .. }
+            | Self::Split { padding, .. }
+            | Self::Grid { padding, .. } => *padding = new,
+            _ => {}
+        }
+        self
+    }
 }
 
 impl Layout {
-    /// add an item, returns whether layout is updated
-    pub fn add(
-        &mut self,
-        at: &[Direction],
-        split: &Direction,
-        constraint_1: Constraint,
-        constraint_2: Constraint,
-        component: Option<Discriminator>,
-        border: Option<Border>,
-    ) -> bool {
-        if at.is_empty() {
-            match split {
-                Direction::Up => {
-                    *self = Self::vertical(
-                        Layout::single(component, border),
-                        std::mem::take(self),
-                        constraint_1,
-                        constraint_2,
-                    )
-                }
-                Direction::Down => {
-                    *self = Self::vertical(
-                        std::mem::take(self),
-                        Layout::single(component, border),
-                        constraint_1,
-                        constraint_2,
-                    )
-                }
-                Direction::Left => {
-                    *self = Self::horizontal(
-                        Layout::single(component, border),
-                        std::mem::take(self),
-                        constraint_1,
-                        constraint_2,
-                    )
-                }
-                Direction::Right => {
-                    *self = Self::horizontal(
-                        std::mem::take(self),
-                        Layout::single(component, border),
-                        constraint_1,
-                        constraint_2,
-                    )
-                }
-            }
-
-            return true;
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Self::None => None,
+            Self::Single { id, .. }
+            | Self::SplitHorizontal { id, .. }
+            | Self::SplitVertical { id, .. }
+            | Self::Split { id, .. }
+            | Self::Tabs { id, .. }
+            | Self::Grid { id, .. }
+            | Self::Responsive { id, .. } => id.as_deref(),
         }
+    }
 
+    /// set the id of the node, has no effect on `Layout::None`
+    pub fn set_id(&mut self, new: Option<String>) {
         match self {
-            Self::SplitHorizontal { left, right, .. }
-                if matches!(at[0], Direction::Left | Direction::Right) =>
-            {
-                if at[0] == Direction::Left {
-                    left.add(
-                        &at[1..],
-                        split,
-                        constraint_1,
-                        constraint_2,
-                        component,
-                        border,
-                    )
-                } else {
-                    right.add(
-                        &at[1..],
-                        split,
-                        constraint_1,
-                        constraint_2,
-                        component,
-                        border,
-                    )
+            Self::None => {}
+            Self::Single { id, .. }
+            | Self::SplitHorizontal { id, .. }
+            | Self::SplitVertical { id, .. }
+            | Self::Split { id, .. }
+            | Self::Tabs { id, .. }
+            | Self::Grid { id, .. }
+            | Self::Responsive { id, .. } => *id = new,
+        }
+    }
+
+    /// generate ids for all nodes without one, using and incrementing `next`,
+    /// ids already in the layout are skipped
+    pub fn assign_ids(&mut self, next: &mut u64) {
+        let used: HashSet<String> = self.ids().into_iter().map(str::to_string).collect();
+        self.assign_unused_ids(next, &used);
+    }
+
+    fn assign_unused_ids(&mut self, next: &mut u64, used: &HashSet<String>) {
+        if !matches!(self, Self::None) && self.id().is_none() {
+            let id = loop {
+                let id = format!("#{next}");
+                *next += 1;
+
+                if !used.contains(&id) {
+                    break id;
                 }
-            }
-            Self::SplitVertical { top, bottom, .. }
-                if matches!(at[0], Direction::Up | Direction::Down) =>
-            {
-                if at[0] == Direction::Up {
-                    top.add(
-                        &at[1..],
-                        split,
-                        constraint_1,
-                        constraint_2,
-                        component,
-                        border,
-                    )
-                } else {
-                    bottom.add(
-                        &at[1..],
-                        split,
-                        constraint_1,
-                        constraint_2,
-                        component,
-                        border,
-                    )
+            };
+            self.set_id(Some(id));
+        }
+
+        self.children_mut()
+            .into_iter()
+            .for_each(|child| child.assign_unused_ids(next, used));
+    }
+
+    /// ids of the node and all nodes below it
+    pub fn ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.id().into_iter().collect();
+        self.children()
+            .into_iter()
+            .for_each(|(_, child)| ids.extend(child.ids()));
+        ids
+    }
+
+    /// fails if an id in `layout` is used twice, or is used in the tree
+    /// outside of the node at `replaced`, which `layout` takes the place of
+    fn check_ids(&self, replaced: Option<&[Step]>, layout: &Layout) -> Result<(), LayoutError> {
+        let mut used: HashMap<&str, usize> = HashMap::new();
+        self.ids()
+            .into_iter()
+            .for_each(|id| *used.entry(id).or_default() += 1);
+
+        if let Some(replaced) = replaced {
+            self.lookup(replaced)?.ids().into_iter().for_each(|id| {
+                if let Some(count) = used.get_mut(id) {
+                    *count -= 1;
                 }
+            });
+        }
+
+        for id in layout.ids() {
+            let count = used.entry(id).or_default();
+
+            if *count > 0 {
+                return Err(LayoutError::DuplicateId { id: id.to_string() });
             }
-            _ => false,
+
+            *count += 1;
         }
+
+        Ok(())
     }
 
-    /// remove an item, returns whether layout is updated
-    pub fn remove(&mut self, at: &[Direction]) -> bool {
-        if at.is_empty() {
-            *self = Self::None;
-            return true;
+    /// path to the first node with the id
+    pub fn find_id(&self, id: &str) -> Option<Vec<Step>> {
+        if self.id() == Some(id) {
+            return Some(Vec::new());
         }
 
-        if at.len() == 1 {
-            match self {
-                Self::SplitHorizontal { left, right, .. }
-                    if matches!(at[0], Direction::Left | Direction::Right) =>
-                {
-                    if at[0] == Direction::Left {
-                        *self = std::mem::take(right);
-                    } else {
-                        *self = std::mem::take(left);
-                    }
+        self.children().into_iter().find_map(|(step, child)| {
+            let mut path = child.find_id(id)?;
+            path.insert(0, step);
+            Some(path)
+        })
+    }
 
-                    return true;
-                }
-                Self::SplitVertical { top, bottom, .. }
-                    if matches!(at[0], Direction::Up | Direction::Down) =>
-                {
-                    if at[0] == Direction::Up {
-                        *self = std::mem::take(bottom);
-                    } else {
-                        *self = std::mem::take(top);
-                    }
+    /// path to the pane containing the component
+    ///
+    /// alternatives of a responsive layout usually share components,
+    /// so the one shown on the screen is searched first
+    pub fn find(
+        &self,
+        component: &Discriminator,
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> Option<Vec<Step>> {
+        if let Self::Single {
+            discrim: Some(discrim),
+            ..
+        } = self
+        {
+            return (discrim == component).then(Vec::new);
+        }
 
-                    return true;
-                }
-                _ => {}
-            }
+        let mut children: Vec<_> = self
+            .children()
+            .into_iter()
+            .zip(self.child_rects(&screen, hints))
+            .collect();
+
+        if let Some(index) = self.active_alternative(&screen) {
+            children[..=index].rotate_right(1);
         }
 
+        children.into_iter().find_map(|((step, child), rect)| {
+            let mut path = child.find(component, rect, hints)?;
+            path.insert(0, step);
+            Some(path)
+        })
+    }
+
+    /// components not hidden in an inactive tab
+    pub fn visible(&self) -> Vec<Discriminator> {
         match self {
-            Self::SplitHorizontal { left, right, .. }
-                if matches!(at[0], Direction::Left | Direction::Right) =>
-            {
-                if at[0] == Direction::Left {
-                    left.remove(&at[1..])
-                } else {
-                    right.remove(&at[1..])
-                }
-            }
-            Self::SplitVertical { top, bottom, .. }
-                if matches!(at[0], Direction::Up | Direction::Down) =>
-            {
-                if at[0] == Direction::Up {
-                    top.remove(&at[1..])
-                } else {
-                    bottom.remove(&at[1..])
-                }
+            Self::Tabs { active, tabs, .. } => tabs
+                .get(*active)
+                .map(|tab| tab.layout.visible())
+                .unwrap_or_default(),
+            Self::Single { .. } => self.components(),
+            _ => self
+                .children()
+                .into_iter()
+                .flat_map(|(_, child)| child.visible())
+                .collect(),
+        }
+    }
+
+    /// in every tabs node, make the first tab with one of the components active
+    pub fn show(&mut self, components: &[Discriminator]) {
+        if let Self::Tabs { active, tabs, .. } = self {
+            if let Some(index) = tabs.iter().position(|tab| {
+                tab.layout
+                    .components()
+                    .iter()
+                    .any(|component| components.contains(component))
+            }) {
+                *active = index;
             }
-            _ => false,
         }
+
+        self.children_mut()
+            .into_iter()
+            .for_each(|child| child.show(components));
+    }
+
+    /// path to the smallest node containing all of the components in the layout
+    pub fn enclosing(
+        &self,
+        components: &[Discriminator],
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> Option<Vec<Step>> {
+        let mut paths = components
+            .iter()
+            .filter_map(|component| self.find(component, screen, hints));
+        let first = paths.next()?;
+
+        Some(paths.fold(first, |common, path| {
+            common
+                .into_iter()
+                .zip(path)
+                .take_while(|(a, b)| a == b)
+                .map(|(step, _)| step)
+                .collect()
+        }))
     }
 
-    pub fn set(&mut self, at: &[Direction], state: Layout) -> bool {
-        if at.is_empty() {
-            *self = state;
-            return true;
+    /// turn a target into a path to an existing node
+    pub fn resolve(
+        &self,
+        target: &Target,
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> Result<Vec<Step>, LayoutError> {
+        match target {
+            Target::Path(path) => self.lookup(path).map(|_| path.clone()),
+            Target::Id { id } => self.find_id(id).ok_or(LayoutError::NotFound),
+            Target::Component { component } => self
+                .find(component, screen, hints)
+                .ok_or(LayoutError::NotFound),
         }
+    }
+}
 
+impl Layout {
+    /// all direct children with the step leading to them
+    fn children(&self) -> Vec<(Step, &Self)> {
         match self {
-            Self::SplitHorizontal { left, right, .. }
-                if matches!(at[0], Direction::Left | Direction::Right) =>
-            {
-                if at[0] == Direction::Left {
-                    left.set(&at[1..], state)
-                } else {
-                    right.set(&at[1..], state)
-                }
-            }
-            Self::SplitVertical { top, bottom, .. }
-                if matches!(at[0], Direction::Up | Direction::Down) =>
-            {
-                if at[0] == Direction::Up {
-                    top.set(&at[1..], state)
-                } else {
-                    bottom.set(&at[1..], state)
-                }
+            Self::None | Self::Single { .. } => Vec::new(),
+            Self::SplitHorizontal { left, right, .. } => vec![
+                (Step::Direction(Direction::Left), left.as_ref()),
+                (Step::Direction(Direction::Right), right.as_ref()),
+            ],
+            Self::SplitVertical { top, bottom, .. } => vec![
+                (Step::Direction(Direction::Up), top.as_ref()),
+                (Step::Direction(Direction::Down), bottom.as_ref()),
+            ],
+            Self::Split { children, .. } => children
+                .iter()
+                .enumerate()
+                .map(|(index, (_, child))| (Step::Index(index), child))
+                .collect(),
+            Self::Tabs { tabs, .. } => tabs
+                .iter()
+                .enumerate()
+                .map(|(index, tab)| (Step::Index(index), &tab.layout))
+                .collect(),
+            Self::Grid { cells, .. } => cells
+                .iter()
+                .enumerate()
+                .map(|(index, cell)| (Step::Index(index), &cell.layout))
+                .collect(),
+            Self::Responsive { alternatives, .. } => alternatives
+                .iter()
+                .enumerate()
+                .map(|(index, alternative)| (Step::Index(index), &alternative.layout))
+                .collect(),
+        }
+    }
+
+    /// all direct children, mutably
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        match self {
+            Self::None | Self::Single { .. } => Vec::new(),
+            Self::SplitHorizontal { left, right, .. } => vec![left.as_mut(), right.as_mut()],
+            Self::SplitVertical { top, bottom, .. } => vec![top.as_mut(), bottom.as_mut()],
+            Self::Split { children, .. } => children.iter_mut().map(|(_, child)| child).collect(),
+            Self::Tabs { tabs, .. } => tabs.iter_mut().map(|tab| &mut tab.layout).collect(),
+            Self::Grid { cells, .. } => cells.iter_mut().map(|cell| &mut cell.layout).collect(),
+            Self::Responsive { alternatives, .. } => alternatives
+                .iter_mut()
+                .map(|alternative| &mut alternative.layout)
+                .collect(),
+        }
+    }
+
+    /// index of the direct child the step points to
+    fn child_index(&self, step: &Step) -> Option<usize> {
+        let index = match self {
+            Self::SplitHorizontal { .. } => step.index(Orientation::Horizontal),
+            Self::SplitVertical { .. } => step.index(Orientation::Vertical),
+            Self::Split { .. }
+            | Self::Tabs { .. }
+            | Self::Grid { .. }
+            | Self::Responsive { .. } => match step {
+                Step::Index(index) => Some(*index),
+                Step::Direction(_) => None,
+            },
+            _ => None,
+        }?;
+
+        (index < self.children().len()).then_some(index)
+    }
+
+    /// get a direct child of a split
+    fn child(&self, step: &Step) -> Option<&Self> {
+        let index = self.child_index(step)?;
+        Some(self.children().swap_remove(index).1)
+    }
+
+    /// why `step` does not lead to a child
+    fn missing(&self, step: &Step) -> LayoutError {
+        match (self, step) {
+            (Self::SplitHorizontal { .. } | Self::SplitVertical { .. }, Step::Direction(_)) => {
+                LayoutError::WrongSplitDirection
             }
-            _ => false,
+            _ => LayoutError::NotFound,
         }
     }
 
-    pub fn get(&self, at: &[Direction]) -> Option<&Self> {
-        if at.is_empty() {
-            return Some(self);
+    /// get a direct child of a split mutably
+    fn child_mut(&mut self, step: &Step) -> Option<&mut Self> {
+        let index = self.child_index(step)?;
+        Some(self.children_mut().swap_remove(index))
+    }
+
+    /// orientation and constraints of the children of a split
+    fn split_constraints(&self) -> Option<(Orientation, Vec<&Constraint>)> {
+        match self {
+            Self::SplitHorizontal {
+                left_constraint,
+                right_constraint,
+                ..
+            } => Some((
+                Orientation::Horizontal,
+                vec![left_constraint, right_constraint],
+            )),
+            Self::SplitVertical {
+                top_constraint,
+                bottom_constraint,
+                ..
+            } => Some((
+                Orientation::Vertical,
+                vec![top_constraint, bottom_constraint],
+            )),
+            Self::Split {
+                orientation,
+                children,
+                ..
+            } => Some((
+                *orientation,
+                children.iter().map(|(constraint, _)| constraint).collect(),
+            )),
+            _ => None,
         }
+    }
 
+    /// constraints of the children of a split, mutably
+    fn split_constraints_mut(&mut self) -> Option<Vec<&mut Constraint>> {
         match self {
-            Self::SplitHorizontal { left, right, .. }
-                if matches!(at[0], Direction::Left | Direction::Right) =>
-            {
-                if at[0] == Direction::Left {
-                    left.get(&at[1..])
-                } else {
-                    right.get(&at[1..])
-                }
-            }
-            Self::SplitVertical { top, bottom, .. }
-                if matches!(at[0], Direction::Up | Direction::Down) =>
-            {
-                if at[0] == Direction::Up {
-                    top.get(&at[1..])
-                } else {
-                    bottom.get(&at[1..])
-                }
-            }
+            Self::SplitHorizontal {
+                left_constraint,
+                right_constraint,
+                ..
+            } => Some(vec![left_constraint, right_constraint]),
+            Self::SplitVertical {
+                top_constraint,
+                bottom_constraint,
+                ..
+            } => Some(vec![top_constraint, bottom_constraint]),
+            Self::Split { children, .. } => Some(
+                children
+                    .iter_mut()
+                    .map(|(constraint, _)| constraint)
+                    .collect(),
+            ),
             _ => None,
         }
     }
 
-    pub fn areas(&self, screen: Rect, client: &Client) -> Vec<(Rect, Discriminator)> {
-        let mut areas: Vec<(Rect, Discriminator)> = Vec::new();
+    /// minimum and preferred length along the orientation, from the size hints
+    /// of the components in the node, including space taken by borders and tab bars
+    pub fn hinted_size(&self, orientation: Orientation, hints: &SizeHints) -> HintedSize {
+        // space around the components, only added if they have a size
+        let padded = |size: HintedSize, padding: u32| HintedSize {
+            min: if size.min > 0 {
+                size.min.saturating_add(padding)
+            } else {
+                0
+            },
+            preferred: size
+                .preferred
+                .map(|preferred| preferred.saturating_add(padding)),
+        };
 
         match self {
-            Self::None => {}
+            Self::None => HintedSize::default(),
             Self::Single {
                 discrim,
-                border: Some(border),
+                border,
+                padding,
+                ..
             } => {
-                if screen.width > 1 && screen.height > 1 {
-                    let borderset: BorderSet = (&border.r#type).into();
-
-                    client.setcharcoloured(
-                        screen.x,
-                        screen.y,
-                        borderset.topleft,
-                        border.colour,
-                        Colour::Reset,
-                    );
-                    client.setcharcoloured(
-                        screen.x + screen.width - 1,
-                        screen.y,
-                        borderset.topright,
-                        border.colour,
-                        Colour::Reset,
-                    );
-                    client.setcharcoloured(
-                        screen.x + screen.width - 1,
-                        screen.y + screen.height - 1,
-                        borderset.bottomright,
-                        border.colour,
-                        Colour::Reset,
-                    );
-                    client.setcharcoloured(
-                        screen.x,
-                        screen.y + screen.height - 1,
-                        borderset.bottomleft,
-                        border.colour,
-                        Colour::Reset,
-                    );
-
-                    (screen.x + 1..screen.x + screen.width - 1).for_each(|x| {
-                        client.setcharcoloured(
-                            x,
-                            screen.y,
-                            borderset.top,
-                            border.colour,
-                            Colour::Reset,
-                        );
-                        client.setcharcoloured(
-                            x,
-                            screen.y + screen.height - 1,
-                            borderset.bottom,
-                            border.colour,
-                            Colour::Reset,
-                        );
-                    });
-
-                    (screen.y + 1..screen.y + screen.height - 1).for_each(|y| {
-                        client.setcharcoloured(
-                            screen.x,
-                            y,
-                            borderset.left,
-                            border.colour,
-                            Colour::Reset,
-                        );
-                        client.setcharcoloured(
-                            screen.x + screen.width - 1,
-                            y,
-                            borderset.right,
-                            border.colour,
-                            Colour::Reset,
-                        );
-                    });
+                let hint = discrim
+                    .as_ref()
+                    .and_then(|discrim| hints.get(discrim))
+                    .copied()
+                    .unwrap_or_default();
 
-                    if let Some(discrim) = discrim {
-                        if screen.width > 2 && screen.height > 2 {
-                            areas.push((
-                                Rect::new(
-                                    screen.x + 1,
-                                    screen.y + 1,
-                                    screen.width - 2,
-                                    screen.height - 2,
-                                ),
-                                discrim.clone(),
-                            ))
-                        } else {
-                            areas.push((Rect::new(0, 0, 0, 0), discrim.clone()))
-                        }
-                    }
-                } else if let Some(discrim) = discrim {
-                    areas.push((Rect::new(0, 0, 0, 0), discrim.clone()))
-                }
+                padded(
+                    HintedSize {
+                        min: hint.min(orientation),
+                        preferred: hint.preferred(orientation),
+                    },
+                    padding
+                        .saturating_mul(2)
+                        .saturating_add(if border.is_some() { 2 } else { 0 }),
+                )
             }
-            Self::Single { discrim, .. } => {
-                if let Some(discrim) = discrim {
-                    areas.push((screen, discrim.clone()))
-                }
+            Self::Tabs { tabs, .. } => {
+                let sizes: Vec<HintedSize> = tabs
+                    .iter()
+                    .map(|tab| tab.layout.hinted_size(orientation, hints))
+                    .collect();
+
+                padded(
+                    HintedSize {
+                        min: sizes.iter().map(|size| size.min).max().unwrap_or(0),
+                        preferred: sizes.iter().filter_map(|size| size.preferred).max(),
+                    },
+                    // room for the tab bar
+                    if orientation == Orientation::Vertical {
+                        1
+                    } else {
+                        0
+                    },
+                )
             }
-            Self::SplitVertical {
-                top_constraint,
-                top,
-                bottom_constraint,
-                bottom,
-            } => {
-                let top_height = top_constraint.eval(screen.height);
-                let bottom_height = bottom_constraint
-                    .eval(screen.height)
-                    .min(screen.height - top_height);
+            Self::Responsive { alternatives, .. } => {
+                let sizes: Vec<HintedSize> = alternatives
+                    .iter()
+                    .map(|alternative| alternative.layout.hinted_size(orientation, hints))
+                    .collect();
 
-                areas.extend(top.areas(
-                    Rect::new(screen.x, screen.y, screen.width, top_height),
-                    client,
-                ));
-                areas.extend(bottom.areas(
-                    Rect::new(screen.x, screen.y + top_height, screen.width, bottom_height),
-                    client,
-                ));
+                HintedSize {
+                    min: sizes.iter().map(|size| size.min).min().unwrap_or(0),
+                    preferred: sizes.iter().filter_map(|size| size.preferred).min(),
+                }
             }
-            Self::SplitHorizontal {
-                left_constraint,
-                left,
-                right_constraint,
-                right,
-            } => {
-                let left_width = left_constraint.eval(screen.width);
-                let right_width = right_constraint
-                    .eval(screen.width)
-                    .min(screen.width - left_width);
+            _ => {
+                // the rows or columns of a grid lie along the orientation like children of a split
+                let (sizes, along) = match self {
+                    Self::Grid { .. } => (self.track_sizes(orientation, hints), true),
+                    _ => (
+                        self.children()
+                            .into_iter()
+                            .map(|(_, child)| child.hinted_size(orientation, hints))
+                            .collect(),
+                        matches!(
+                            self.split_constraints(),
+                            Some((split, _)) if split == orientation
+                        ),
+                    ),
+                };
+                let (gap, padding) = self.spacing();
+                let gaps = if along && !self.collapsed() {
+                    gap.saturating_mul((sizes.len() as u32).saturating_sub(1))
+                } else {
+                    0
+                };
+                // shared borders are only counted once
+                let overlaps = if along {
+                    self.overlaps(orientation)
+                        .into_iter()
+                        .filter(|overlap| *overlap)
+                        .count() as u32
+                } else {
+                    0
+                };
+                let combine = |lengths: Vec<u32>| -> u32 {
+                    if along {
+                        lengths
+                            .into_iter()
+                            .fold(0, u32::saturating_add)
+                            .saturating_sub(overlaps)
+                    } else {
+                        lengths.into_iter().max().unwrap_or(0)
+                    }
+                };
 
-                areas.extend(left.areas(
-                    Rect::new(screen.x, screen.y, left_width, screen.height),
-                    client,
-                ));
-                areas.extend(right.areas(
-                    Rect::new(screen.x + left_width, screen.y, right_width, screen.height),
-                    client,
-                ));
+                padded(
+                    HintedSize {
+                        min: combine(sizes.iter().map(|size| size.min).collect()),
+                        // children without a preferred size count with their minimum
+                        preferred: sizes.iter().any(|size| size.preferred.is_some()).then(|| {
+                            combine(
+                                sizes
+                                    .iter()
+                                    .map(|size| size.preferred.unwrap_or(size.min))
+                                    .collect(),
+                            )
+                        }),
+                    },
+                    gaps.saturating_add(padding.saturating_mul(2)),
+                )
             }
         }
-
-        areas
+    }
+
+    /// size of each row (vertical) or column (horizontal) of a grid,
+    /// the size of a cell spanning several of them is spread over all of them
+    fn track_sizes(&self, orientation: Orientation, hints: &SizeHints) -> Vec<HintedSize> {
+        let Self::Grid {
+            rows,
+            columns,
+            cells,
+            ..
+        } = self
+        else {
+            return Vec::new();
+        };
+
+        let count = match orientation {
+            Orientation::Horizontal => columns.len(),
+            Orientation::Vertical => rows.len(),
+        };
+        let mut sizes = vec![HintedSize::default(); count];
+
+        cells
+            .iter()
+            // cells outside the grid are hidden
+            .filter(|cell| cell.row < rows.len() && cell.column < columns.len())
+            .for_each(|cell| {
+                let (start, end) = cell.tracks(orientation, count);
+                let share = |length: u32| length.div_ceil((end - start) as u32);
+                let size = cell.layout.hinted_size(orientation, hints);
+
+                sizes[start..end].iter_mut().for_each(|track| {
+                    track.min = track.min.max(share(size.min));
+                    track.preferred = track.preferred.max(size.preferred.map(share));
+                });
+            });
+
+        sizes
+    }
+
+    /// gap between the children and padding around them of a split or grid
+    fn spacing(&self) -> (u32, u32) {
+        match self {
+            Self::SplitHorizontal { gap, padding, .. }
+            | Self::SplitVertical { gap, padding, .. }
+            | Self::Split { gap, padding, .. }
+            | Self::Grid { gap, padding, .. } => (*gap, *padding),
+            _ => (0, 0),
+        }
+    }
+
+    /// whether the children of a split or grid share the borders between them
+    fn collapsed(&self) -> bool {
+        match self {
+            Self::SplitHorizontal { collapse, .. }
+            | Self::SplitVertical { collapse, .. }
+            | Self::Split { collapse, .. }
+            | Self::Grid { collapse, .. } => *collapse,
+            _ => false,
+        }
+    }
+
+    /// whether the node draws a border all around its area, which a neighbour can share
+    fn bordered(&self) -> bool {
+        matches!(
+            self,
+            Self::Single {
+                border: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// for every pair of adjacent children (or rows or columns of a grid) along the orientation,
+    /// whether they overlap by a cell to share the border between them
+    ///
+    /// only children of a collapsed split or grid overlap, and only if both sides draw a border
+    fn overlaps(&self, orientation: Orientation) -> Vec<bool> {
+        match self {
+            Self::Grid {
+                rows,
+                columns,
+                cells,
+                ..
+            } => {
+                let count = match orientation {
+                    Orientation::Horizontal => columns.len(),
+                    Orientation::Vertical => rows.len(),
+                };
+
+                (1..count)
+                    .map(|boundary| {
+                        self.collapsed()
+                            && cells
+                                .iter()
+                                .filter(|cell| cell.row < rows.len() && cell.column < columns.len())
+                                .filter(|cell| {
+                                    let (start, end) = cell.tracks(orientation, count);
+                                    start == boundary || end == boundary
+                                })
+                                .all(|cell| cell.layout.bordered())
+                    })
+                    .collect()
+            }
+            _ => self
+                .children()
+                .windows(2)
+                .map(|pair| self.collapsed() && pair[0].1.bordered() && pair[1].1.bordered())
+                .collect(),
+        }
+    }
+
+    /// area inside the padding, the gap between `count` children along the orientation,
+    /// and the length left for the children themselves
+    ///
+    /// gaps shrink before the children do, so small areas never underflow,
+    /// collapsed children have no gap and get a cell more for each of the `overlaps`
+    fn spaced(
+        &self,
+        screen: &Rect,
+        orientation: Orientation,
+        count: usize,
+        overlaps: &[bool],
+    ) -> (Rect, u32, u32) {
+        let (gap, padding) = self.spacing();
+        let inner = inset(screen, padding);
+        let length = orientation.length(&inner);
+        let gaps = (count as u32).saturating_sub(1);
+
+        if self.collapsed() {
+            let overlaps = overlaps.iter().filter(|overlap| **overlap).count() as u32;
+            return (inner, 0, length + overlaps);
+        }
+
+        let gap = length
+            .saturating_sub(count as u32)
+            .checked_div(gaps)
+            .map_or(0, |max| gap.min(max));
+
+        (inner, gap, length - gap * gaps)
+    }
+
+    /// area given to each child, in the same order as `children`
+    fn child_rects(&self, screen: &Rect, hints: &SizeHints) -> Vec<Rect> {
+        match self {
+            Self::Responsive { alternatives, .. } => {
+                let active = self.active_alternative(screen);
+
+                return (0..alternatives.len())
+                    .map(|index| {
+                        if Some(index) == active {
+                            Rect::new(screen.x, screen.y, screen.width, screen.height)
+                        } else {
+                            Rect::new(0, 0, 0, 0)
+                        }
+                    })
+                    .collect();
+            }
+            Self::Tabs { active, tabs, .. } => {
+                return (0..tabs.len())
+                    .map(|index| {
+                        if index == *active && screen.height > 1 {
+                            Rect::new(screen.x, screen.y + 1, screen.width, screen.height - 1)
+                        } else {
+                            Rect::new(0, 0, 0, 0)
+                        }
+                    })
+                    .collect()
+            }
+            Self::Grid {
+                rows,
+                columns,
+                cells,
+                ..
+            } => {
+                // start and end offset of every row and column, so cells spanning
+                // the same rows or columns line up exactly
+                let tracks = |constraints: &Vec<Constraint>, orientation: Orientation| {
+                    let overlaps = self.overlaps(orientation);
+                    let (inner, gap, length) =
+                        self.spaced(screen, orientation, constraints.len(), &overlaps);
+                    let end = orientation.length(&inner);
+                    let mut start = 0;
+
+                    split_lengths(
+                        &constraints.iter().collect::<Vec<_>>(),
+                        &self.track_sizes(orientation, hints),
+                        length,
+                    )
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, size)| {
+                        let overlap = overlaps.get(index).copied().unwrap_or(false) as u32;
+                        let track = (start.min(end), (start + size).min(end));
+                        start += (size + gap).saturating_sub(overlap);
+                        track
+                    })
+                    .collect::<Vec<_>>()
+                };
+                let row_tracks = tracks(rows, Orientation::Vertical);
+                let column_tracks = tracks(columns, Orientation::Horizontal);
+                let (inner, _, _) = self.spaced(screen, Orientation::Horizontal, 0, &[]);
+
+                return cells
+                    .iter()
+                    .map(|cell| {
+                        if cell.row >= rows.len() || cell.column >= columns.len() {
+                            return Rect::new(0, 0, 0, 0);
+                        }
+
+                        let (_, bottom) = cell.tracks(Orientation::Vertical, rows.len());
+                        let (_, right) = cell.tracks(Orientation::Horizontal, columns.len());
+
+                        Rect::new(
+                            inner.x + column_tracks[cell.column].0,
+                            inner.y + row_tracks[cell.row].0,
+                            column_tracks[right - 1].1 - column_tracks[cell.column].0,
+                            row_tracks[bottom - 1].1 - row_tracks[cell.row].0,
+                        )
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+
+        let Some((orientation, constraints)) = self.split_constraints() else {
+            return Vec::new();
+        };
+
+        let sizes: Vec<HintedSize> = self
+            .children()
+            .into_iter()
+            .map(|(_, child)| child.hinted_size(orientation, hints))
+            .collect();
+        let overlaps = self.overlaps(orientation);
+        let (inner, gap, length) = self.spaced(screen, orientation, constraints.len(), &overlaps);
+        let mut offset = 0;
+
+        split_lengths(&constraints, &sizes, length)
+            .into_iter()
+            .enumerate()
+            .map(|(index, size)| {
+                let overlap = overlaps.get(index).copied().unwrap_or(false) as u32;
+                let size = size.min(orientation.length(&inner).saturating_sub(offset));
+                let rect = orientation.slice(&inner, offset, size);
+                offset += (size + gap).saturating_sub(overlap);
+                rect
+            })
+            .collect()
+    }
+
+    /// area taken up by the node at `at`
+    pub fn rect(&self, at: &[Step], screen: Rect, hints: &SizeHints) -> Option<Rect> {
+        match at.split_first() {
+            None => Some(screen),
+            Some((step, rest)) => {
+                let index = self.child_index(step)?;
+                let rect = self.child_rects(&screen, hints).swap_remove(index);
+                self.children().swap_remove(index).1.rect(rest, rect, hints)
+            }
+        }
+    }
+
+    /// add an item
+    ///
+    /// in an n-ary split of the same orientation, the item becomes a sibling of the node instead,
+    /// `constraint_1` goes to whichever of the two comes first
+    pub fn add(
+        &mut self,
+        at: &[Step],
+        split: &Direction,
+        constraint_1: Constraint,
+        constraint_2: Constraint,
+        component: Option<Discriminator>,
+        border: Option<Border>,
+    ) -> Result<(), LayoutError> {
+        self.insert(
+            at,
+            split,
+            constraint_1,
+            constraint_2,
+            Layout::single(component, border),
+        )
+    }
+
+    /// split the node at `at`, placing `layout` on the `split` side of it
+    ///
+    /// in an n-ary split of the same orientation, `layout` becomes a sibling of the node instead,
+    /// `constraint_1` goes to whichever of the two comes first
+    ///
+    /// fails if an id in `layout` is already in use
+    pub fn insert(
+        &mut self,
+        at: &[Step],
+        split: &Direction,
+        constraint_1: Constraint,
+        constraint_2: Constraint,
+        layout: Layout,
+    ) -> Result<(), LayoutError> {
+        self.check_ids(None, &layout)?;
+        self.insert_at(at, split, constraint_1, constraint_2, layout)
+    }
+
+    fn insert_at(
+        &mut self,
+        at: &[Step],
+        split: &Direction,
+        constraint_1: Constraint,
+        constraint_2: Constraint,
+        layout: Layout,
+    ) -> Result<(), LayoutError> {
+        if let (
+            [Step::Index(index)],
+            Self::Split {
+                orientation,
+                children,
+                ..
+            },
+        ) = (at, &mut *self)
+        {
+            if *index < children.len() && *orientation == Orientation::of(split) {
+                if matches!(split, Direction::Left | Direction::Up) {
+                    children[*index].0 = constraint_2;
+                    children.insert(*index, (constraint_1, layout));
+                } else {
+                    children[*index].0 = constraint_1;
+                    children.insert(*index + 1, (constraint_2, layout));
+                }
+
+                return Ok(());
+            }
+        }
+
+        if let Some((step, rest)) = at.split_first() {
+            return match self.child_mut(step) {
+                Some(child) => child.insert_at(rest, split, constraint_1, constraint_2, layout),
+                None => Err(self.missing(step)),
+            };
+        }
+
+        match split {
+            Direction::Up => {
+                *self = Self::vertical(layout, std::mem::take(self), constraint_1, constraint_2)
+            }
+            Direction::Down => {
+                *self = Self::vertical(std::mem::take(self), layout, constraint_1, constraint_2)
+            }
+            Direction::Left => {
+                *self = Self::horizontal(layout, std::mem::take(self), constraint_1, constraint_2)
+            }
+            Direction::Right => {
+                *self = Self::horizontal(std::mem::take(self), layout, constraint_1, constraint_2)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// exchange two nodes, constraints stay with their position in the tree,
+    /// fails if one node contains the other
+    pub fn swap(&mut self, a: &[Step], b: &[Step]) -> Result<(), LayoutError> {
+        let (a, b) = (self.indexed(a)?, self.indexed(b)?);
+
+        if a.starts_with(&b) || b.starts_with(&a) {
+            return Err(LayoutError::Overlapping);
+        }
+
+        // both paths exist and neither contains the other, so these cannot fail
+        let node_a = std::mem::take(self.get_mut(&a).ok_or(LayoutError::NotFound)?);
+        let node_b = std::mem::replace(self.get_mut(&b).ok_or(LayoutError::NotFound)?, node_a);
+        *self.get_mut(&a).ok_or(LayoutError::NotFound)? = node_b;
+
+        Ok(())
+    }
+
+    /// move the node at `from` to the `split` side of the node at `to`,
+    /// the layout is left as it was if the move fails
+    pub fn move_node(
+        &mut self,
+        from: &[Step],
+        to: &[Step],
+        split: &Direction,
+        constraint_1: Constraint,
+        constraint_2: Constraint,
+    ) -> Result<(), LayoutError> {
+        let (from, to) = (self.indexed(from)?, self.indexed(to)?);
+
+        if from.is_empty() || to.starts_with(&from) {
+            return Err(LayoutError::Overlapping);
+        }
+
+        // the node is swapped for a placeholder until it is inserted,
+        // as inserting may change the path leading to it
+        let ids = self.ids();
+        let mut id = String::from("#moving");
+        while ids.contains(&id.as_str()) {
+            id.push('_');
+        }
+        let mut placeholder = Self::single(None, None);
+        placeholder.set_id(Some(id.clone()));
+
+        let mut layout = self.clone();
+        let node = std::mem::replace(
+            layout.get_mut(&from).ok_or(LayoutError::NotFound)?,
+            placeholder,
+        );
+        layout.insert(&to, split, constraint_1, constraint_2, node)?;
+
+        let at = layout.find_id(&id).ok_or(LayoutError::NotFound)?;
+        layout.remove(&at)?;
+
+        *self = layout;
+        Ok(())
+    }
+
+    /// grow or shrink the node at `at` by `delta`
+    ///
+    /// see `resize_with` for how the size is applied
+    pub fn resize(
+        &mut self,
+        at: &[Step],
+        delta: i32,
+        options: ResizeOptions,
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> Result<(), LayoutError> {
+        self.resize_with(at, options, screen, hints, |current, parent| {
+            current as i64
+                + match options.unit {
+                    Unit::Cells => delta as i64,
+                    Unit::Percent => delta as i64 * parent as i64 / 100,
+                }
+        })
+    }
+
+    /// set the size of the node at `at`
+    ///
+    /// see `resize_with` for how the size is applied
+    pub fn resize_to(
+        &mut self,
+        at: &[Step],
+        size: u32,
+        options: ResizeOptions,
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> Result<(), LayoutError> {
+        self.resize_with(at, options, screen, hints, |_, parent| match options.unit {
+            Unit::Cells => size as i64,
+            Unit::Percent => size as i64 * parent as i64 / 100,
+        })
+    }
+
+    /// resize the child of the nearest ancestor split with the orientation
+    /// that contains `at`, the space is taken from or given to its next sibling
+    /// (or previous sibling for the last child)
+    ///
+    /// the constraints of both are rewritten in `unit`, and neither goes below `min`
+    fn resize_with(
+        &mut self,
+        at: &[Step],
+        options: ResizeOptions,
+        screen: Rect,
+        hints: &SizeHints,
+        new_size: impl FnOnce(u32, u32) -> i64,
+    ) -> Result<(), LayoutError> {
+        let ResizeOptions {
+            orientation,
+            unit,
+            min,
+        } = options;
+
+        self.lookup(at)?;
+
+        let Some(depth) = (0..at.len()).rev().find(|depth| {
+            matches!(
+                self.get(&at[..*depth]).and_then(Self::split_constraints),
+                Some((split, _)) if split == orientation
+            )
+        }) else {
+            return Err(LayoutError::WrongSplitDirection);
+        };
+
+        let rect = self.rect(&at[..depth], screen, hints).unwrap();
+        let parent = self.get(&at[..depth]).unwrap();
+        let index = parent.child_index(&at[depth]).unwrap();
+        let (_, constraints) = parent.split_constraints().unwrap();
+
+        let sizes: Vec<HintedSize> = parent
+            .children()
+            .into_iter()
+            .map(|(_, child)| child.hinted_size(orientation, hints))
+            .collect();
+
+        let overlaps = parent.overlaps(orientation);
+        let (_, _, length) = parent.spaced(&rect, orientation, constraints.len(), &overlaps);
+        let lengths = split_lengths(&constraints, &sizes, length);
+
+        let sibling = if index + 1 < lengths.len() {
+            index + 1
+        } else if index > 0 {
+            index - 1
+        } else {
+            return Err(LayoutError::NoSpace);
+        };
+
+        let total = lengths[index] + lengths[sibling];
+
+        if length == 0 || total < min.saturating_mul(2) {
+            return Err(LayoutError::NoSpace);
+        }
+
+        // the hinted minimums hold as well, as long as both fit
+        let (min_index, min_sibling) = (min.max(sizes[index].min), min.max(sizes[sibling].min));
+        let (min_index, min_sibling) = if min_index.saturating_add(min_sibling) <= total {
+            (min_index, min_sibling)
+        } else {
+            (min, min)
+        };
+
+        let size = new_size(lengths[index], length)
+            .clamp(min_index as i64, (total - min_sibling) as i64) as u32;
+
+        let constraint = |size: u32| -> Constraint {
+            match unit {
+                Unit::Cells => ConstraintVariant::length(size).into(),
+                Unit::Percent => ConstraintVariant::percentage(
+                    (size as f32 * 100_f32 / length as f32).round() as u32,
+                )
+                .into(),
+            }
+        };
+
+        let mut constraints = self
+            .get_mut(&at[..depth])
+            .and_then(Self::split_constraints_mut)
+            .unwrap();
+        *constraints[index] = constraint(size);
+        *constraints[sibling] = constraint(total - size);
+
+        Ok(())
+    }
+
+    /// remove an item
+    pub fn remove(&mut self, at: &[Step]) -> Result<(), LayoutError> {
+        match at {
+            [] => {
+                *self = Self::None;
+                Ok(())
+            }
+            [step] => self.remove_child(step),
+            [step, rest @ ..] => match self.child_mut(step) {
+                Some(child) => child.remove(rest),
+                None => Err(self.missing(step)),
+            },
+        }
+    }
+
+    /// remove every pane containing the component, returns whether any was removed
+    pub fn remove_component(
+        &mut self,
+        component: &Discriminator,
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> bool {
+        let mut removed = false;
+
+        while let Some(at) = self.find(component, screen, hints) {
+            if self.remove(&at).is_err() {
+                break;
+            }
+            removed = true;
+        }
+
+        removed
+    }
+
+    /// remove a direct child of a split, the split collapses if only one child is left
+    fn remove_child(&mut self, step: &Step) -> Result<(), LayoutError> {
+        if self.child_index(step).is_none() {
+            return Err(self.missing(step));
+        }
+
+        match self {
+            Self::SplitHorizontal { left, right, .. } => {
+                match step.index(Orientation::Horizontal) {
+                    Some(0) => *self = std::mem::take(right),
+                    Some(1) => *self = std::mem::take(left),
+                    _ => return Err(LayoutError::NotFound),
+                }
+            }
+            Self::SplitVertical { top, bottom, .. } => match step.index(Orientation::Vertical) {
+                Some(0) => *self = std::mem::take(bottom),
+                Some(1) => *self = std::mem::take(top),
+                _ => return Err(LayoutError::NotFound),
+            },
+            Self::Split { children, .. } => match step {
+                Step::Index(index) if *index < children.len() => {
+                    children.remove(*index);
+
+                    if children.len() == 1 {
+                        let (_, last) = children.pop().unwrap();
+                        *self = last;
+                    } else if children.is_empty() {
+                        *self = Self::None;
+                    }
+                }
+                _ => return Err(LayoutError::NotFound),
+            },
+            Self::Grid { cells, .. } => match step {
+                Step::Index(index) if *index < cells.len() => {
+                    cells.remove(*index);
+                }
+                _ => return Err(LayoutError::NotFound),
+            },
+            Self::Responsive { alternatives, .. } => match step {
+                Step::Index(index) if *index < alternatives.len() => {
+                    alternatives.remove(*index);
+
+                    if alternatives.is_empty() {
+                        *self = Self::None;
+                    }
+                }
+                _ => return Err(LayoutError::NotFound),
+            },
+            Self::Tabs { active, tabs, .. } => match step {
+                Step::Index(index) if *index < tabs.len() => {
+                    tabs.remove(*index);
+
+                    if tabs.is_empty() {
+                        *self = Self::None;
+                    } else if *index < *active || *active == tabs.len() {
+                        *active -= 1;
+                    }
+                }
+                _ => return Err(LayoutError::NotFound),
+            },
+            _ => return Err(LayoutError::NotFound),
+        }
+
+        Ok(())
+    }
+
+    /// replace the node at `at`, fails if it would leave two nodes with the same id
+    pub fn set(&mut self, at: &[Step], state: Layout) -> Result<(), LayoutError> {
+        self.check_ids(Some(at), &state)?;
+
+        if let Some(node) = self.get_mut(at) {
+            *node = state;
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, at: &[Step]) -> Option<&Self> {
+        match at.split_first() {
+            None => Some(self),
+            Some((step, rest)) => self.child(step)?.get(rest),
+        }
+    }
+
+    /// the path to the node at `at` with every step as an index,
+    /// so paths to the same node compare equal
+    fn indexed(&self, at: &[Step]) -> Result<Vec<Step>, LayoutError> {
+        match at.split_first() {
+            None => Ok(Vec::new()),
+            Some((step, rest)) => match (self.child_index(step), self.child(step)) {
+                (Some(index), Some(child)) => {
+                    let mut path = child.indexed(rest)?;
+                    path.insert(0, Step::Index(index));
+                    Ok(path)
+                }
+                _ => Err(self.missing(step)),
+            },
+        }
+    }
+
+    pub fn get_mut(&mut self, at: &[Step]) -> Option<&mut Self> {
+        match at.split_first() {
+            None => Some(self),
+            Some((step, rest)) => self.child_mut(step)?.get_mut(rest),
+        }
+    }
+
+    /// like `get`, but with the reason if there is no node at `at`
+    pub fn lookup(&self, at: &[Step]) -> Result<&Self, LayoutError> {
+        match at.split_first() {
+            None => Ok(self),
+            Some((step, rest)) => match self.child(step) {
+                Some(child) => child.lookup(rest),
+                None => Err(self.missing(step)),
+            },
+        }
+    }
+
+    /// the tabs node at `at`, with its active index and tabs
+    fn tabs_mut(&mut self, at: &[Step]) -> Result<(&mut usize, &mut Vec<Tab>), LayoutError> {
+        self.lookup(at)?;
+
+        match self.get_mut(at) {
+            Some(Self::Tabs { active, tabs, .. }) => Ok((active, tabs)),
+            _ => Err(LayoutError::WrongNodeType),
+        }
+    }
+
+    /// switch the active tab
+    pub fn set_tab(&mut self, at: &[Step], index: usize) -> Result<(), LayoutError> {
+        let (active, tabs) = self.tabs_mut(at)?;
+
+        if index >= tabs.len() {
+            return Err(LayoutError::NotFound);
+        }
+
+        *active = index;
+        Ok(())
+    }
+
+    /// cycle to the next tab, wrapping around
+    pub fn next_tab(&mut self, at: &[Step]) -> Result<(), LayoutError> {
+        let (active, tabs) = self.tabs_mut(at)?;
+
+        if tabs.is_empty() {
+            return Err(LayoutError::NotFound);
+        }
+
+        *active = (*active + 1) % tabs.len();
+        Ok(())
+    }
+
+    /// cycle to the previous tab, wrapping around
+    pub fn prev_tab(&mut self, at: &[Step]) -> Result<(), LayoutError> {
+        let (active, tabs) = self.tabs_mut(at)?;
+
+        if tabs.is_empty() {
+            return Err(LayoutError::NotFound);
+        }
+
+        *active = (*active + tabs.len() - 1) % tabs.len();
+        Ok(())
+    }
+
+    pub fn areas(
+        &self,
+        screen: Rect,
+        client: &Client,
+        hints: &SizeHints,
+    ) -> Vec<(Rect, Discriminator)> {
+        let mut canvas = LineCanvas::default();
+        let areas = self.draw(screen, client, hints, &mut canvas);
+        canvas.render(client);
+
+        areas
+    }
+
+    /// draw the layout, with borders drawn to the canvas so they can be joined
+    fn draw(
+        &self,
+        screen: Rect,
+        client: &Client,
+        hints: &SizeHints,
+        canvas: &mut LineCanvas,
+    ) -> Vec<(Rect, Discriminator)> {
+        let mut areas: Vec<(Rect, Discriminator)> = Vec::new();
+
+        match self {
+            Self::None => {}
+            Self::Single {
+                discrim,
+                border: Some(border),
+                aspect_ratio,
+                padding,
+                ..
+            } => {
+                if screen.width > 1 && screen.height > 1 {
+                    canvas.border(&screen, border);
+
+                    if let Some(discrim) = discrim {
+                        if screen.width > 2 && screen.height > 2 {
+                            let inner = inset(
+                                &Rect::new(
+                                    screen.x + 1,
+                                    screen.y + 1,
+                                    screen.width - 2,
+                                    screen.height - 2,
+                                ),
+                                *padding,
+                            );
+
+                            areas.push((
+                                match aspect_ratio {
+                                    Some(aspect_ratio) => aspect_ratio.letterbox(inner),
+                                    None => inner,
+                                },
+                                discrim.clone(),
+                            ))
+                        } else {
+                            areas.push((Rect::new(0, 0, 0, 0), discrim.clone()))
+                        }
+                    }
+                } else if let Some(discrim) = discrim {
+                    areas.push((Rect::new(0, 0, 0, 0), discrim.clone()))
+                }
+            }
+            Self::Single {
+                discrim,
+                aspect_ratio,
+                padding,
+                ..
+            } => {
+                if let Some(discrim) = discrim {
+                    let inner = inset(&screen, *padding);
+
+                    areas.push((
+                        match aspect_ratio {
+                            Some(aspect_ratio) => aspect_ratio.letterbox(inner),
+                            None => inner,
+                        },
+                        discrim.clone(),
+                    ))
+                }
+            }
+            Self::SplitHorizontal { .. }
+            | Self::SplitVertical { .. }
+            | Self::Split { .. }
+            | Self::Grid { .. } => {
+                areas.extend(self.children_areas(&screen, client, hints, canvas))
+            }
+            Self::Tabs {
+                active,
+                tabs,
+                border,
+                ..
+            } => {
+                if screen.height > 1 {
+                    Self::draw_tab_bar(active, tabs, border.as_ref(), &screen, client);
+                }
+
+                areas.extend(self.children_areas(&screen, client, hints, canvas))
+            }
+            Self::Responsive { .. } => match self.active_alternative(&screen) {
+                // alternatives usually share components, so only the ones
+                // missing from the active alternative are hidden
+                Some(index) => areas.extend(self.draw_zoomed(
+                    &[Step::Index(index)],
+                    screen,
+                    client,
+                    hints,
+                    canvas,
+                )),
+                None => areas.extend(
+                    self.components()
+                        .into_iter()
+                        .map(|discrim| (Rect::new(0, 0, 0, 0), discrim)),
+                ),
+            },
+        }
+
+        areas
+    }
+
+    /// index of the first alternative matching the area
+    fn active_alternative(&self, screen: &Rect) -> Option<usize> {
+        match self {
+            Self::Responsive { alternatives, .. } => alternatives
+                .iter()
+                .position(|alternative| alternative.matches(screen)),
+            _ => None,
+        }
+    }
+
+    /// splits and grids whose constraints cannot be satisfied on the screen, with their paths,
+    /// solved with the size hints as when allocating areas
+    pub fn unsatisfiable(
+        &self,
+        screen: Rect,
+        hints: &SizeHints,
+    ) -> Vec<(Vec<Step>, Unsatisfiable)> {
+        let mut out = Vec::new();
+
+        if let Some((orientation, constraints)) = self.split_constraints() {
+            let overlaps = self.overlaps(orientation);
+            let (_, _, length) = self.spaced(&screen, orientation, constraints.len(), &overlaps);
+            let sizes: Vec<HintedSize> = self
+                .children()
+                .into_iter()
+                .map(|(_, child)| child.hinted_size(orientation, hints))
+                .collect();
+            if let Err(err) = solve_with_hints(&constraints, &sizes, length) {
+                out.push((Vec::new(), err));
+            }
+        }
+
+        if let Self::Grid { rows, columns, .. } = self {
+            [
+                (rows, Orientation::Vertical),
+                (columns, Orientation::Horizontal),
+            ]
+            .into_iter()
+            .for_each(|(constraints, orientation)| {
+                let overlaps = self.overlaps(orientation);
+                let (_, _, length) =
+                    self.spaced(&screen, orientation, constraints.len(), &overlaps);
+                if let Err(err) = solve_with_hints(
+                    &constraints.iter().collect::<Vec<_>>(),
+                    &self.track_sizes(orientation, hints),
+                    length,
+                ) {
+                    out.push((Vec::new(), err));
+                }
+            });
+        }
+
+        self.children()
+            .into_iter()
+            .zip(self.child_rects(&screen, hints))
+            .for_each(|((step, child), rect)| {
+                out.extend(
+                    child
+                        .unsatisfiable(rect, hints)
+                        .into_iter()
+                        .map(|(mut path, err)| {
+                            path.insert(0, step.clone());
+                            (path, err)
+                        }),
+                )
+            });
+
+        out
+    }
+
+    /// give the whole screen to the node at `at`, all other components get an empty area
+    pub fn zoomed_areas(
+        &self,
+        at: &[Step],
+        screen: Rect,
+        client: &Client,
+        hints: &SizeHints,
+    ) -> Vec<(Rect, Discriminator)> {
+        let mut canvas = LineCanvas::default();
+        let areas = self.draw_zoomed(at, screen, client, hints, &mut canvas);
+        canvas.render(client);
+
+        areas
+    }
+
+    fn draw_zoomed(
+        &self,
+        at: &[Step],
+        screen: Rect,
+        client: &Client,
+        hints: &SizeHints,
+        canvas: &mut LineCanvas,
+    ) -> Vec<(Rect, Discriminator)> {
+        let Some(node) = self.get(at) else {
+            return self.draw(screen, client, hints, canvas);
+        };
+
+        let mut areas: Vec<(Rect, Discriminator)> = self
+            .outside(at)
+            .into_iter()
+            .map(|discrim| (Rect::new(0, 0, 0, 0), discrim))
+            .collect();
+        areas.extend(node.draw(screen, client, hints, canvas));
+
+        areas
+    }
+
+    /// components outside of the node at `at`, which are hidden while it is zoomed
+    fn outside(&self, at: &[Step]) -> Vec<Discriminator> {
+        let shown = self.get(at).map(Self::components).unwrap_or_default();
+
+        self.components()
+            .into_iter()
+            .filter(|discrim| !shown.contains(discrim))
+            .collect()
+    }
+
+    /// areas of all children, each laid out in its part of the screen
+    fn children_areas(
+        &self,
+        screen: &Rect,
+        client: &Client,
+        hints: &SizeHints,
+        canvas: &mut LineCanvas,
+    ) -> Vec<(Rect, Discriminator)> {
+        self.children()
+            .into_iter()
+            .zip(self.child_rects(screen, hints))
+            .flat_map(|((_, child), rect)| child.draw(rect, client, hints, canvas))
+            .collect()
+    }
+
+    /// draw the titles of all tabs in the top row of the area,
+    /// the active tab is wrapped in brackets
+    fn draw_tab_bar(
+        active: &usize,
+        tabs: &[Tab],
+        border: Option<&Border>,
+        screen: &Rect,
+        client: &Client,
+    ) {
+        let (colour, borderset) = match border {
+            Some(border) => (border.colour, BorderSet::from(&border.r#type)),
+            None => (Colour::Reset, BorderSet::from(&BorderType::Normal)),
+        };
+
+        let mut cells: Vec<(char, Colour)> = Vec::new();
+
+        tabs.iter().enumerate().for_each(|(index, tab)| {
+            let title = if tab.title.is_empty() {
+                (index + 1).to_string()
+            } else {
+                tab.title.clone()
+            };
+            let (open, close) = if index == *active {
+                ('[', ']')
+            } else {
+                (' ', ' ')
+            };
+
+            cells.push((open, colour));
+            cells.extend(title.chars().map(|c| (c, Colour::Reset)));
+            cells.push((close, colour));
+            cells.push((borderset.right, colour));
+        });
+
+        (0..screen.width).for_each(|offset| {
+            let (c, fg) = cells
+                .get(offset as usize)
+                .copied()
+                .unwrap_or((borderset.top, colour));
+            client.setcharcoloured(screen.x + offset, screen.y, c, fg, Colour::Reset);
+        });
     }
 
     pub fn components(&self) -> Vec<Discriminator> {
@@ -452,8 +1984,842 @@ impl Layout {
                 out.append(&mut top.components());
                 out.append(&mut bottom.components());
             }
+            Self::Split { children, .. } => children
+                .iter()
+                .for_each(|(_, child)| out.append(&mut child.components())),
+            Self::Tabs { tabs, .. } => tabs
+                .iter()
+                .for_each(|tab| out.append(&mut tab.layout.components())),
+            Self::Grid { cells, .. } => cells
+                .iter()
+                .for_each(|cell| out.append(&mut cell.layout.components())),
+            Self::Responsive { alternatives, .. } => alternatives
+                .iter()
+                .flat_map(|alternative| alternative.layout.components())
+                .for_each(|discrim| {
+                    if !out.contains(&discrim) {
+                        out.push(discrim)
+                    }
+                }),
         }
 
         out
     }
 }
+
+/// the rect shrunk by `padding` on every side, but never by more than half its size
+fn inset(rect: &Rect, padding: u32) -> Rect {
+    let x = padding.min(rect.width / 2);
+    let y = padding.min(rect.height / 2);
+
+    Rect::new(
+        rect.x + x,
+        rect.y + y,
+        rect.width - x * 2,
+        rect.height - y * 2,
+    )
+}
+
+/// lengths of the children of a split, sized from the hinted sizes where possible,
+/// falling back to the best effort lengths if the constraints cannot be satisfied
+fn split_lengths(constraints: &[&Constraint], sizes: &[HintedSize], length: u32) -> Vec<u32> {
+    solve_with_hints(constraints, sizes, length).unwrap_or_else(|err| err.lengths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstraintVariant, SizeHint};
+
+    fn component(n: u32) -> Discriminator {
+        serde_json::from_value(serde_json::json!([n])).unwrap()
+    }
+
+    fn pane(n: u32) -> Layout {
+        Layout::single(Some(component(n)), None)
+    }
+
+    fn screen() -> Rect {
+        Rect::new(0, 0, 100, 20)
+    }
+
+    fn half() -> Constraint {
+        Constraint::new(ConstraintVariant::percentage(50), None, None)
+    }
+
+    fn row(panes: &[u32]) -> Layout {
+        Layout::split(
+            Orientation::Horizontal,
+            panes.iter().map(|n| (half(), pane(*n))).collect(),
+        )
+    }
+
+    /// 1 above 2 on the left, 3 on the right
+    fn tree() -> Layout {
+        Layout::horizontal(
+            Layout::vertical(pane(1), pane(2), half(), half()),
+            pane(3),
+            half(),
+            half(),
+        )
+    }
+
+    fn tabs(panes: &[u32]) -> Layout {
+        Layout::tabs(
+            panes
+                .iter()
+                .map(|n| Tab::new(String::new(), pane(*n)))
+                .collect(),
+            None,
+        )
+    }
+
+    fn active(layout: &Layout) -> usize {
+        match layout {
+            Layout::Tabs { active, .. } => *active,
+            _ => panic!("not a tabs node"),
+        }
+    }
+
+    #[test]
+    fn add_beside_a_row_child_adds_a_sibling() {
+        let mut layout = row(&[1, 2]);
+
+        assert!(layout
+            .add(
+                &[Step::Index(1)],
+                &Direction::Right,
+                half(),
+                half(),
+                Some(component(3)),
+                None,
+            )
+            .is_ok());
+        assert!(layout
+            .add(
+                &[Step::Index(0)],
+                &Direction::Left,
+                half(),
+                half(),
+                Some(component(4)),
+                None,
+            )
+            .is_ok());
+
+        match &layout {
+            Layout::Split { children, .. } => assert_eq!(children.len(), 4),
+            _ => panic!("row should stay a single split"),
+        }
+        assert!(
+            layout.components() == vec![component(4), component(1), component(2), component(3)]
+        );
+
+        // across the row, the pane is nested instead
+        assert!(layout
+            .add(
+                &[Step::Index(0)],
+                &Direction::Down,
+                half(),
+                half(),
+                Some(component(5)),
+                None,
+            )
+            .is_ok());
+        assert!(matches!(
+            layout.get(&[Step::Index(0)]),
+            Some(Layout::SplitVertical { .. })
+        ));
+    }
+
+    #[test]
+    fn tab_switching_wraps_around() {
+        let mut layout = tabs(&[1, 2, 3]);
+
+        assert!(layout.prev_tab(&[]).is_ok());
+        assert_eq!(active(&layout), 2);
+        assert!(layout.next_tab(&[]).is_ok());
+        assert_eq!(active(&layout), 0);
+        assert!(layout.set_tab(&[], 1).is_ok());
+        assert_eq!(active(&layout), 1);
+
+        assert!(layout.set_tab(&[], 3) == Err(LayoutError::NotFound));
+        assert!(pane(1).next_tab(&[]) == Err(LayoutError::WrongNodeType));
+        assert_eq!(active(&layout), 1);
+    }
+
+    #[test]
+    fn removing_a_tab_keeps_the_active_tab() {
+        let mut layout = tabs(&[1, 2, 3]);
+        assert!(layout.set_tab(&[], 1).is_ok());
+
+        // a tab before the active one, the same tab stays active
+        assert!(layout.remove(&[Step::Index(0)]).is_ok());
+        assert_eq!(active(&layout), 0);
+        assert!(layout.components() == vec![component(2), component(3)]);
+
+        // the active tab itself, the next tab takes its place
+        assert!(layout.remove(&[Step::Index(0)]).is_ok());
+        assert_eq!(active(&layout), 0);
+
+        // the active last tab, the one before it becomes active
+        let mut layout = tabs(&[1, 2, 3]);
+        assert!(layout.set_tab(&[], 2).is_ok());
+        assert!(layout.remove(&[Step::Index(2)]).is_ok());
+        assert_eq!(active(&layout), 1);
+
+        let mut layout = tabs(&[1]);
+        assert!(layout.remove(&[Step::Index(0)]).is_ok());
+        assert!(matches!(layout, Layout::None));
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let mut layout = row(&[1, 2]);
+        assert!(layout
+            .insert(
+                &[Step::Index(1)],
+                &Direction::Down,
+                half(),
+                half(),
+                pane(3).with_id("#0".to_string()),
+            )
+            .is_ok());
+
+        let duplicate = pane(4).with_id("#0".to_string());
+        assert!(
+            layout.insert(&[], &Direction::Down, half(), half(), duplicate)
+                == Err(LayoutError::DuplicateId {
+                    id: "#0".to_string()
+                })
+        );
+        assert!(layout.components().len() == 3);
+
+        let mut next = 0;
+        layout.assign_ids(&mut next);
+        let mut ids = layout.ids();
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+
+        // replacing a node may reuse the ids inside it
+        let replacement = pane(5).with_id("#0".to_string());
+        assert!(layout
+            .set(&layout.find_id("#0").unwrap(), replacement)
+            .is_ok());
+        assert!(
+            layout.set(&[Step::Index(0)], pane(6).with_id("#0".to_string()))
+                == Err(LayoutError::DuplicateId {
+                    id: "#0".to_string()
+                })
+        );
+    }
+
+    #[test]
+    fn targets_resolve_to_paths() {
+        let mut layout = row(&[1, 2]);
+        assert!(layout
+            .insert(
+                &[Step::Index(1)],
+                &Direction::Down,
+                half(),
+                half(),
+                pane(3).with_id("status".to_string()),
+            )
+            .is_ok());
+
+        let path = layout
+            .resolve(
+                &Target::Component {
+                    component: component(3),
+                },
+                screen(),
+                &SizeHints::new(),
+            )
+            .unwrap();
+        assert!(layout.get(&path).unwrap().id() == Some("status"));
+        let path = layout
+            .resolve(
+                &Target::Id {
+                    id: "status".to_string(),
+                },
+                screen(),
+                &SizeHints::new(),
+            )
+            .unwrap();
+        assert!(layout.get(&path).unwrap().components() == vec![component(3)]);
+
+        assert!(
+            layout.resolve(
+                &Target::Component {
+                    component: component(4)
+                },
+                screen(),
+                &SizeHints::new()
+            ) == Err(LayoutError::NotFound)
+        );
+        assert!(
+            layout.resolve(
+                &Target::Path(vec![Step::Index(2)]),
+                screen(),
+                &SizeHints::new()
+            ) == Err(LayoutError::NotFound)
+        );
+    }
+
+    #[test]
+    fn swap_rejects_aliased_overlap() {
+        let mut layout = tree();
+        let inside = layout
+            .find(&component(1), screen(), &SizeHints::new())
+            .unwrap();
+
+        assert!(layout.swap(&[Step::Index(0)], &inside) == Err(LayoutError::Overlapping));
+        assert!(layout.components() == tree().components());
+    }
+
+    #[test]
+    fn move_rejects_aliased_overlap() {
+        let mut layout = tree();
+        let inside = layout
+            .find(&component(1), screen(), &SizeHints::new())
+            .unwrap();
+
+        assert!(
+            layout.move_node(
+                &[Step::Index(0)],
+                &inside,
+                &Direction::Right,
+                half(),
+                half(),
+            ) == Err(LayoutError::Overlapping)
+        );
+        assert!(layout.components() == tree().components());
+    }
+
+    #[test]
+    fn swap_and_move_accept_mixed_paths() {
+        let mut layout = tree();
+        assert!(layout
+            .swap(
+                &[Step::Index(0), Step::Index(1)],
+                &[Direction::Right.into()],
+            )
+            .is_ok());
+        assert!(layout.components() == vec![component(1), component(3), component(2)]);
+
+        let mut layout = tree();
+        assert!(layout
+            .move_node(
+                &[Step::Index(1)],
+                &[Direction::Left.into(), Step::Index(0)],
+                &Direction::Right,
+                half(),
+                half(),
+            )
+            .is_ok());
+        assert!(layout.components() == vec![component(1), component(3), component(2)]);
+    }
+
+    #[test]
+    fn move_within_a_row_keeps_siblings() {
+        let mut layout = row(&[1, 2, 3]);
+
+        assert!(layout
+            .move_node(
+                &[Step::Index(2)],
+                &[Step::Index(0)],
+                &Direction::Left,
+                half(),
+                half(),
+            )
+            .is_ok());
+
+        assert!(layout.components() == vec![component(3), component(1), component(2)]);
+    }
+
+    #[test]
+    fn resize_moves_space_between_siblings() {
+        let screen = Rect::new(0, 0, 100, 20);
+        let hints = SizeHints::new();
+        let mut layout = row(&[1, 2, 3]);
+        let middle: &[Step] = &[Step::Index(1)];
+        let cells = |min| ResizeOptions::new(Orientation::Horizontal, Unit::Cells, min);
+        let lengths = |layout: &Layout| -> Vec<u32> {
+            layout
+                .child_rects(&screen, &hints)
+                .iter()
+                .map(|rect| rect.width)
+                .collect()
+        };
+
+        // the space comes from the next sibling, the first pane keeps its length
+        assert_eq!(lengths(&layout), vec![33, 33, 34]);
+        assert!(layout.resize(middle, 10, cells(1), screen, &hints).is_ok());
+        assert_eq!(lengths(&layout), vec![33, 43, 24]);
+
+        let mut layout = row(&[1, 2]);
+        let percent = ResizeOptions::new(Orientation::Horizontal, Unit::Percent, 1);
+        assert!(layout
+            .resize_to(middle, 30, percent, screen, &hints)
+            .is_ok());
+        assert_eq!(lengths(&layout), vec![70, 30]);
+
+        // neither pane goes below the minimum
+        assert!(layout.resize(middle, 80, cells(5), screen, &hints).is_ok());
+        assert_eq!(lengths(&layout), vec![5, 95]);
+
+        let vertical = ResizeOptions::new(Orientation::Vertical, Unit::Cells, 1);
+        assert!(
+            layout.resize(middle, 1, vertical, screen, &hints)
+                == Err(LayoutError::WrongSplitDirection)
+        );
+        assert!(layout.resize(middle, 1, cells(51), screen, &hints) == Err(LayoutError::NoSpace));
+        assert!(
+            layout.resize(middle, 1, cells(u32::MAX), screen, &hints) == Err(LayoutError::NoSpace)
+        );
+    }
+
+    #[test]
+    fn zooming_hides_everything_else() {
+        let layout = tree();
+
+        assert!(layout.outside(&[Direction::Left.into()]) == vec![component(3)]);
+        assert!(
+            layout.outside(&[Step::Index(0), Step::Index(1)]) == vec![component(1), component(3)]
+        );
+        assert!(layout.outside(&[]).is_empty());
+    }
+
+    #[test]
+    fn regenerated_layouts_keep_what_was_shown() {
+        let mut layout = tabs(&[1, 2, 3]);
+        assert!(layout.set_tab(&[], 2).is_ok());
+        assert!(layout.visible() == vec![component(3)]);
+
+        // the same components in another order, the tab showing 3 becomes active
+        let mut regenerated = Layout::tabs(
+            [3, 1, 2]
+                .into_iter()
+                .map(|n| Tab::new(String::new(), pane(n)))
+                .collect(),
+            None,
+        );
+        regenerated.show(&layout.visible());
+        assert_eq!(active(&regenerated), 0);
+
+        let layout = tree();
+        let at = layout
+            .enclosing(&[component(1), component(2)], screen(), &SizeHints::new())
+            .unwrap();
+        assert!(layout.get(&at).unwrap().components() == vec![component(1), component(2)]);
+        let at = layout
+            .enclosing(&[component(2), component(4)], screen(), &SizeHints::new())
+            .unwrap();
+        assert!(layout.get(&at).unwrap().components() == vec![component(2)]);
+        assert!(layout
+            .enclosing(&[component(1), component(3)], screen(), &SizeHints::new())
+            .unwrap()
+            .is_empty());
+        assert!(layout
+            .enclosing(&[component(4)], screen(), &SizeHints::new())
+            .is_none());
+    }
+
+    #[test]
+    fn grid_cells_line_up_across_spans() {
+        let screen = Rect::new(2, 1, 60, 30);
+        let third = || Constraint::new(ConstraintVariant::percentage(33), None, None);
+        let layout = Layout::grid(
+            vec![half(), half()],
+            vec![third(), third(), third()],
+            vec![
+                GridCell::new(0, 0, 1, 2, pane(1)),
+                GridCell::new(0, 2, 2, 1, pane(2)),
+                GridCell::new(1, 0, 1, 1, pane(3)),
+                GridCell::new(1, 1, 1, 1, pane(4)),
+                // spans past the last row are cut off, cells outside the grid are hidden
+                GridCell::new(1, 1, 5, 1, pane(5)),
+                GridCell::new(2, 0, 1, 1, pane(6)),
+            ],
+        );
+        let rects = layout.child_rects(&screen, &SizeHints::new());
+
+        assert!(rects[0] == Rect::new(2, 1, 40, 15));
+        assert!(rects[1] == Rect::new(42, 1, 20, 30));
+        assert!(rects[2] == Rect::new(2, 16, 20, 15));
+        // the spanning cell ends exactly where the cells below it do
+        assert!(rects[3] == Rect::new(22, 16, 20, 15));
+        assert_eq!(rects[2].width + rects[3].width, rects[0].width);
+        assert!(rects[4] == rects[3]);
+        assert!(rects[5] == Rect::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn fills_share_by_weight() {
+        let lengths = |constraints: &[ConstraintVariant], length| {
+            let constraints: Vec<Constraint> =
+                constraints.iter().cloned().map(Constraint::from).collect();
+            split_lengths(&constraints.iter().collect::<Vec<_>>(), &[], length)
+        };
+
+        assert_eq!(
+            lengths(
+                &[ConstraintVariant::fill(1), ConstraintVariant::fill(3)],
+                100
+            ),
+            vec![25, 75]
+        );
+        assert_eq!(
+            lengths(
+                &[
+                    ConstraintVariant::length(20),
+                    ConstraintVariant::fill(1),
+                    ConstraintVariant::fill(3)
+                ],
+                100
+            ),
+            vec![20, 20, 60]
+        );
+        assert_eq!(
+            lengths(
+                &[ConstraintVariant::fill(1), ConstraintVariant::length(3)],
+                24
+            ),
+            vec![21, 3]
+        );
+        // rounding leftovers go to the first fills
+        assert_eq!(
+            lengths(&[ConstraintVariant::fill(1), ConstraintVariant::fill(1)], 9),
+            vec![5, 4]
+        );
+    }
+
+    #[test]
+    fn unsatisfiable_splits_are_reported_by_path() {
+        let lengths = || {
+            Layout::split(
+                Orientation::Horizontal,
+                vec![
+                    (ConstraintVariant::length(60).into(), pane(1)),
+                    (ConstraintVariant::length(60).into(), pane(2)),
+                ],
+            )
+        };
+        let screen = Rect::new(0, 0, 100, 20);
+        let hints = SizeHints::new();
+
+        assert!(lengths().unsatisfiable(screen, &hints).len() == 1);
+        assert!(lengths()
+            .unsatisfiable(Rect::new(0, 0, 120, 20), &hints)
+            .is_empty());
+
+        let layout = Layout::split(
+            Orientation::Vertical,
+            vec![(half(), pane(3)), (half(), lengths())],
+        );
+        let reported = layout.unsatisfiable(screen, &hints);
+        assert_eq!(reported.len(), 1);
+        assert!(reported[0].0 == vec![Step::Index(1)]);
+        assert_eq!(reported[0].1.required, 120);
+    }
+
+    #[test]
+    fn letterbox_centres_the_largest_fitting_rect() {
+        let rect = Rect::new(10, 5, 40, 10);
+
+        // too wide, bars on the left and right
+        assert!(AspectRatio::new(2, 1).letterbox(rect) == Rect::new(20, 5, 20, 10));
+        // too tall, bars above and below
+        assert!(AspectRatio::new(8, 1).letterbox(rect) == Rect::new(10, 7, 40, 5));
+        assert!(AspectRatio::new(4, 1).letterbox(rect) == rect);
+        assert!(AspectRatio::new(0, 1).letterbox(rect) == rect);
+    }
+
+    #[test]
+    fn breakpoints_match_the_area() {
+        let wide = Rect::new(0, 0, 100, 20);
+        let tall = Rect::new(0, 0, 30, 40);
+
+        assert!(Breakpoint::new(Some(100), Some(20), None, Layout::None).matches(&wide));
+        assert!(!Breakpoint::new(Some(101), None, None, Layout::None).matches(&wide));
+        assert!(!Breakpoint::new(None, Some(21), None, Layout::None).matches(&wide));
+
+        let landscape = Breakpoint::new(None, None, Some(ScreenOrientation::Landscape), pane(1));
+        assert!(landscape.matches(&wide));
+        assert!(!landscape.matches(&tall));
+        // twice as many columns as rows is already landscape
+        assert!(landscape.matches(&Rect::new(0, 0, 40, 20)));
+        assert!(Breakpoint::new(None, None, None, Layout::None).matches(&tall));
+    }
+
+    #[test]
+    fn components_resolve_in_the_active_alternative() {
+        let layout = Layout::Responsive {
+            id: None,
+            alternatives: vec![
+                Breakpoint::new(Some(80), None, None, tree()),
+                Breakpoint::new(None, None, None, tabs(&[1, 2, 3])),
+            ],
+        };
+        let target = Target::Component {
+            component: component(2),
+        };
+
+        let at = layout
+            .resolve(&target, screen(), &SizeHints::new())
+            .unwrap();
+        assert!(at[0] == Step::Index(0));
+        let at = layout
+            .resolve(&target, Rect::new(0, 0, 60, 20), &SizeHints::new())
+            .unwrap();
+        assert!(at == vec![Step::Index(1), Step::Index(1)]);
+        assert!(layout.get(&at).unwrap().components() == vec![component(2)]);
+    }
+
+    fn min_width(n: u32, min_width: u32) -> (Discriminator, SizeHint) {
+        (
+            component(n),
+            SizeHint {
+                min_width,
+                ..SizeHint::default()
+            },
+        )
+    }
+
+    #[test]
+    fn resize_keeps_hinted_minimums() {
+        let screen = Rect::new(0, 0, 10, 10);
+        let hints = SizeHints::from([min_width(2, 6)]);
+        let mut layout = Layout::horizontal(pane(1), pane(2), half(), half());
+        let left: &[Step] = &[Direction::Left.into()];
+        let options = ResizeOptions::new(Orientation::Horizontal, Unit::Cells, 1);
+
+        assert!(layout.resize_to(left, 8, options, screen, &hints).is_ok());
+        assert_eq!(layout.rect(left, screen, &hints).unwrap().width, 4);
+
+        // the minimum and the hint cannot both fit, only the minimum holds
+        let options = ResizeOptions::new(Orientation::Horizontal, Unit::Cells, 5);
+        assert!(layout
+            .resize(&[Direction::Right.into()], 1, options, screen, &hints)
+            .is_ok());
+        match &layout {
+            Layout::SplitHorizontal {
+                left_constraint,
+                right_constraint,
+                ..
+            } => assert_eq!(
+                (left_constraint.eval(10), right_constraint.eval(10)),
+                (5, 5)
+            ),
+            _ => panic!("resizing should keep the split"),
+        }
+
+        let options = ResizeOptions::new(Orientation::Horizontal, Unit::Cells, 6);
+        assert!(layout.resize(left, 1, options, screen, &hints) == Err(LayoutError::NoSpace));
+
+        // hints too large to add up do not overflow
+        let hints = SizeHints::from([min_width(1, u32::MAX), min_width(2, u32::MAX)]);
+        let mut layout = Layout::horizontal(
+            Layout::single(
+                Some(component(1)),
+                Some(Border {
+                    colour: Colour::Reset,
+                    r#type: BorderType::Normal,
+                }),
+            ),
+            pane(2),
+            half(),
+            half(),
+        );
+        assert_eq!(
+            layout.hinted_size(Orientation::Horizontal, &hints).min,
+            u32::MAX
+        );
+        let options = ResizeOptions::new(Orientation::Horizontal, Unit::Cells, 1);
+        assert!(layout.resize(left, 1, options, screen, &hints).is_ok());
+    }
+
+    #[test]
+    fn grid_tracks_keep_hinted_minimums() {
+        let screen = Rect::new(0, 0, 100, 20);
+        let hints = SizeHints::from([min_width(1, 70), min_width(2, 20)]);
+        let layout = Layout::grid(
+            vec![half(), half()],
+            vec![half(), half()],
+            vec![
+                GridCell::new(0, 0, 1, 1, pane(1)),
+                GridCell::new(1, 1, 1, 1, pane(2)),
+                GridCell::new(1, 0, 1, 1, pane(3)),
+            ],
+        );
+
+        let rects = layout.child_rects(&screen, &hints);
+        assert_eq!(rects[0].width, 70);
+        // the whole column is widened, not just the hinted cell
+        assert_eq!(rects[2].width, 70);
+        assert_eq!(rects[1].width, 30);
+        // the minimums of different columns add up, even in different rows
+        assert_eq!(layout.hinted_size(Orientation::Horizontal, &hints).min, 90);
+
+        // a spanning cell spreads its minimum over the columns it spans
+        let hints = SizeHints::from([min_width(1, 90)]);
+        let layout = Layout::grid(
+            vec![half()],
+            vec![half(), half()],
+            vec![GridCell::new(0, 0, 1, 2, pane(1))],
+        );
+        assert_eq!(layout.hinted_size(Orientation::Horizontal, &hints).min, 90);
+
+        // preferred sizes add up per column the same way
+        let hints = SizeHints::from([
+            (
+                component(1),
+                SizeHint {
+                    preferred_width: Some(30),
+                    ..SizeHint::default()
+                },
+            ),
+            min_width(2, 20),
+        ]);
+        let layout = Layout::grid(
+            vec![half(), half()],
+            vec![half(), half()],
+            vec![
+                GridCell::new(0, 0, 1, 1, pane(1)),
+                GridCell::new(1, 1, 1, 1, pane(2)),
+            ],
+        );
+        let size = layout.hinted_size(Orientation::Horizontal, &hints);
+        assert_eq!((size.min, size.preferred), (20, Some(50)));
+    }
+
+    #[test]
+    fn gaps_and_padding_shrink_to_fit() {
+        let hints = SizeHints::new();
+        let fill = || Constraint::from(ConstraintVariant::fill(1));
+        let layout = Layout::split(
+            Orientation::Horizontal,
+            vec![(fill(), pane(1)), (fill(), pane(2)), (fill(), pane(3))],
+        )
+        .with_gap(2)
+        .with_padding(1);
+
+        let rects = layout.child_rects(&Rect::new(0, 0, 32, 10), &hints);
+        assert!(
+            rects
+                == vec![
+                    Rect::new(1, 1, 9, 8),
+                    Rect::new(12, 1, 9, 8),
+                    Rect::new(23, 1, 8, 8)
+                ]
+        );
+
+        // no room for gaps, the panes share what the padding leaves
+        let rects = layout.child_rects(&Rect::new(0, 0, 4, 10), &hints);
+        assert_eq!(rects.iter().map(|rect| rect.width).sum::<u32>(), 2);
+        assert!(rects[0].x == 1);
+
+        assert!(inset(&Rect::new(3, 3, 5, 3), 10) == Rect::new(5, 4, 1, 1));
+        assert!(inset(&Rect::new(3, 3, 5, 3), 1) == Rect::new(4, 4, 3, 1));
+    }
+
+    #[test]
+    fn hinted_sizes_include_spacing() {
+        let hints = SizeHints::from([min_width(1, 10), min_width(2, 10)]);
+        let size = |layout: Layout| layout.hinted_size(Orientation::Horizontal, &hints).min;
+
+        assert_eq!(size(pane(1).with_padding(2)), 14);
+        assert_eq!(
+            size(Layout::horizontal(pane(1), pane(2), half(), half()).with_gap(3)),
+            23
+        );
+        // the gap only lies between children along the split
+        assert_eq!(
+            size(Layout::vertical(pane(1), pane(2), half(), half()).with_gap(3)),
+            10
+        );
+
+        // too large to add up, but not overflowing
+        assert_eq!(size(pane(1).with_padding(u32::MAX)), u32::MAX);
+        assert_eq!(
+            size(Layout::horizontal(pane(1), pane(2), half(), half()).with_gap(u32::MAX)),
+            u32::MAX
+        );
+    }
+
+    fn bordered(n: u32) -> Layout {
+        Layout::single(
+            Some(component(n)),
+            Some(Border {
+                colour: Colour::Reset,
+                r#type: BorderType::Normal,
+            }),
+        )
+    }
+
+    #[test]
+    fn collapse_only_overlaps_bordered_neighbours() {
+        let hints = SizeHints::new();
+        let fill = || Constraint::from(ConstraintVariant::fill(1));
+        let collapsed = |panes: Vec<Layout>| {
+            Layout::split(
+                Orientation::Horizontal,
+                panes.into_iter().map(|pane| (fill(), pane)).collect(),
+            )
+            .with_gap(2)
+            .with_collapse(true)
+        };
+        let edges = |layout: &Layout| -> Vec<(u32, u32)> {
+            layout
+                .child_rects(&Rect::new(0, 0, 10, 5), &hints)
+                .iter()
+                .map(|rect| (rect.x, rect.x + rect.width))
+                .collect()
+        };
+
+        // each divider is shared, so the panes get the two cells saved between them
+        let layout = collapsed(vec![bordered(1), bordered(2), bordered(3)]);
+        assert_eq!(edges(&layout), vec![(0, 4), (3, 7), (6, 10)]);
+
+        // an unbordered pane has nothing to share, but gaps are still dropped
+        let layout = collapsed(vec![bordered(1), pane(2), bordered(3)]);
+        assert_eq!(edges(&layout), vec![(0, 4), (4, 7), (7, 10)]);
+        let layout = collapsed(vec![bordered(1), bordered(2), pane(3)]);
+        assert_eq!(edges(&layout), vec![(0, 4), (3, 7), (7, 10)]);
+
+        let hints = SizeHints::from([min_width(1, 5), min_width(2, 5), min_width(3, 5)]);
+        let size = |layout: Layout| layout.hinted_size(Orientation::Horizontal, &hints).min;
+        assert_eq!(size(collapsed(vec![bordered(1), bordered(2)])), 13);
+        assert_eq!(size(collapsed(vec![bordered(1), pane(2)])), 12);
+    }
+
+    #[test]
+    fn collapsed_grid_tracks_share_bordered_edges() {
+        let hints = SizeHints::new();
+        let layout = |bottom: Layout| {
+            Layout::grid(
+                vec![half(), half()],
+                vec![half(), half()],
+                vec![
+                    GridCell::new(0, 0, 1, 1, bordered(1)),
+                    GridCell::new(0, 1, 1, 1, bordered(2)),
+                    GridCell::new(1, 0, 1, 2, bottom),
+                ],
+            )
+            .with_collapse(true)
+        };
+        let screen = Rect::new(0, 0, 9, 9);
+
+        let rects = layout(bordered(3)).child_rects(&screen, &hints);
+        assert!(rects[0] == Rect::new(0, 0, 5, 5));
+        assert!(rects[1] == Rect::new(4, 0, 5, 5));
+        assert!(rects[2] == Rect::new(0, 4, 9, 5));
+
+        // the bottom cell draws no border, so only the columns still overlap
+        let rects = layout(pane(3)).child_rects(&screen, &hints);
+        assert!(rects[1] == Rect::new(4, 0, 5, rects[0].height));
+        assert!(rects[2].y == rects[0].height);
+        assert_eq!(rects[0].height + rects[2].height, 9);
+    }
+}
@@ -6,3 +6,23 @@ mod request;
 pub use request::*;
 mod border;
 pub use border::*;
+mod path;
+pub use path::*;
+mod floating;
+pub use floating::*;
+mod tiling;
+pub use tiling::*;
+mod solver;
+pub use solver::*;
+mod hint;
+pub use hint::*;
+mod canvas;
+pub use canvas::*;
+mod error;
+pub use error::*;
+mod reply;
+pub use reply::*;
+mod change;
+pub use change::*;
+mod confirm;
+pub use confirm::*;
@@ -1,15 +1,344 @@
-use std::collections::{HashSet, LinkedList};
+use std::collections::{HashMap, HashSet, LinkedList};
 
-use ccanvas_layout::{Layout, LayoutRequest};
+use ccanvas_layout::{
+    Area, ComponentPath, Confirmations, FloatingLayer, Layout, LayoutChanged, LayoutError,
+    LayoutRequest, Reply, ReplyResult, ResizeOptions, SizeHints, Target, Tiled, Warning,
+};
 use libccanvas::{
-    bindings::{Event, EventVariant, Subscription},
+    bindings::{Discriminator, Event, EventVariant, Subscription},
     client::{Client, ClientConfig},
-    features::common::Dimension,
+    features::common::{Dimension, Rect},
+};
+use tokio::{
+    sync::OnceCell,
+    task::JoinSet,
+    time::{self, Duration, Instant},
 };
-use tokio::{sync::OnceCell, task::JoinSet};
 
 const ALLOCATED: &str = "!layout-allocated-rect";
 const CONFIRM: &str = "!layout-render-confirm";
+const HINT: &str = "!layout-size-hint";
+const REPLY: &str = "!layout-reply";
+const CHANGED: &str = "!layout-changed";
+const CONFIRM_FAILED: &str = "!layout-confirm-failed";
+
+/// milliseconds to wait for render confirmations, 0 waits forever
+const TIMEOUT_VAR: &str = "LAYOUT_CONFIRM_TIMEOUT";
+const DEFAULT_TIMEOUT: u64 = 1000;
+/// renders in a row a component can fail to confirm before it is dropped, 0 never drops
+const MISSED_VAR: &str = "LAYOUT_CONFIRM_MISSED";
+const DEFAULT_MISSED: u32 = 3;
+
+/// everything a request can change, cloned so a batch can be rolled back
+#[derive(Clone)]
+struct Daemon {
+    state: Layout,
+    next_id: u64,
+    // the zoomed node, always by id so it survives changes to the tree
+    zoomed: Option<Target>,
+    floating: FloatingLayer,
+    // when set, the layout is generated from a list of components
+    tiling: Option<Tiled>,
+    // components to wait on for a render confirmation before rendering
+    watch: Vec<Discriminator>,
+}
+
+impl Daemon {
+    fn new() -> Self {
+        Self {
+            state: Layout::None,
+            next_id: 0,
+            zoomed: None,
+            floating: FloatingLayer::default(),
+            tiling: None,
+            watch: Vec::new(),
+        }
+    }
+
+    /// apply a request, the layout only changed if the result is `ReplyResult::Ok`
+    fn apply(
+        &mut self,
+        request: LayoutRequest,
+        term_size: Dimension,
+        hints: &SizeHints,
+        allocated: &HashMap<Discriminator, serde_json::Value>,
+    ) -> Result<ReplyResult, LayoutError> {
+        // the tiled layout is regenerated from its list of components, edits would be lost
+        if self.tiling.is_some()
+            && matches!(
+                request,
+                LayoutRequest::Add { .. }
+                    | LayoutRequest::Remove { .. }
+                    | LayoutRequest::SetLayout { .. }
+                    | LayoutRequest::Swap { .. }
+                    | LayoutRequest::Move { .. }
+                    | LayoutRequest::Resize { .. }
+                    | LayoutRequest::ResizeTo { .. }
+            )
+        {
+            return Err(LayoutError::TilingEnabled);
+        }
+
+        match request {
+            LayoutRequest::Add {
+                at,
+                split,
+                constraint_1,
+                constraint_2,
+                component,
+                border,
+                id,
+            } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                let mut layout = Layout::single(component.clone(), border);
+                layout.set_id(id);
+
+                self.state
+                    .insert(&at, &split, constraint_1, constraint_2, layout)?;
+
+                self.watch.extend(component);
+            }
+            LayoutRequest::Remove { at } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.watch.extend(self.state.lookup(&at)?.components());
+
+                self.state.remove(&at)?;
+            }
+            LayoutRequest::SetLayout { at, layout } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.state.set(&at, layout)?;
+
+                self.watch.extend(self.state.components());
+            }
+            LayoutRequest::Swap { a, b } => {
+                let (a, b) = (
+                    self.state.resolve(&a, term_size.into(), hints)?,
+                    self.state.resolve(&b, term_size.into(), hints)?,
+                );
+
+                self.state.swap(&a, &b)?;
+            }
+            LayoutRequest::Move {
+                from,
+                to,
+                split,
+                constraint_1,
+                constraint_2,
+            } => {
+                let (from, to) = (
+                    self.state.resolve(&from, term_size.into(), hints)?,
+                    self.state.resolve(&to, term_size.into(), hints)?,
+                );
+
+                self.state
+                    .move_node(&from, &to, &split, constraint_1, constraint_2)?;
+            }
+            LayoutRequest::Resize {
+                at,
+                orientation,
+                delta,
+                unit,
+                min,
+            } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.state.resize(
+                    &at,
+                    delta,
+                    ResizeOptions::new(orientation, unit, min.unwrap_or(1)),
+                    term_size.into(),
+                    hints,
+                )?;
+            }
+            LayoutRequest::ResizeTo {
+                at,
+                orientation,
+                size,
+                unit,
+                min,
+            } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.state.resize_to(
+                    &at,
+                    size,
+                    ResizeOptions::new(orientation, unit, min.unwrap_or(1)),
+                    term_size.into(),
+                    hints,
+                )?;
+            }
+            LayoutRequest::ToggleZoom { at } => {
+                if self.zoomed.is_some() {
+                    self.zoomed = None;
+                } else {
+                    let at = self.state.resolve(&at, term_size.into(), hints)?;
+                    let id = self.state.lookup(&at)?.id().ok_or(LayoutError::NotFound)?;
+
+                    self.zoomed = Some(Target::Id { id: id.to_string() });
+                }
+            }
+            LayoutRequest::SetTab { at, index } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.state.set_tab(&at, index)?;
+            }
+            LayoutRequest::NextTab { at } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.state.next_tab(&at)?;
+            }
+            LayoutRequest::PrevTab { at } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                self.state.prev_tab(&at)?;
+            }
+            LayoutRequest::SetTiling { algorithm, border } => {
+                self.tiling = algorithm.map(|algorithm| {
+                    let components = match self.tiling.take() {
+                        Some(tiled) => tiled.components,
+                        None => self.state.components(),
+                    };
+
+                    Tiled::new(algorithm, components, border)
+                });
+
+                self.retile(term_size.into(), hints);
+            }
+            LayoutRequest::TileAdd { component, index } => {
+                let tiled = self.tiling.as_mut().ok_or(LayoutError::TilingDisabled)?;
+
+                tiled.add(component.clone(), index);
+                self.retile(term_size.into(), hints);
+
+                self.watch.push(component);
+            }
+            LayoutRequest::TileRemove { component } => {
+                let tiled = self.tiling.as_mut().ok_or(LayoutError::TilingDisabled)?;
+
+                tiled.remove(&component)?;
+                self.retile(term_size.into(), hints);
+            }
+            LayoutRequest::AddFloating(window) => {
+                self.watch.extend(window.layout.components());
+
+                self.floating.add(window);
+            }
+            LayoutRequest::MoveFloating { id, x, y } => self.floating.move_to(&id, x, y)?,
+            LayoutRequest::ResizeFloating { id, width, height } => {
+                self.floating.resize(&id, width, height)?
+            }
+            LayoutRequest::CloseFloating { id } => {
+                self.floating.close(&id).ok_or(LayoutError::NotFound)?;
+            }
+            LayoutRequest::RaiseFloating { id } => self.floating.raise(&id)?,
+            LayoutRequest::LowerFloating { id } => self.floating.lower(&id)?,
+            LayoutRequest::GetLayout { at } => {
+                let at = self.state.resolve(&at, term_size.into(), hints)?;
+
+                return Ok(ReplyResult::Layout {
+                    layout: serde_json::to_value(self.state.lookup(&at)?).unwrap(),
+                });
+            }
+            LayoutRequest::GetAreas => {
+                return Ok(ReplyResult::Areas {
+                    areas: allocated
+                        .iter()
+                        .map(|(component, rect)| Area {
+                            component: component.clone(),
+                            rect: rect.clone(),
+                        })
+                        .collect(),
+                });
+            }
+            LayoutRequest::ListComponents => {
+                let mut components: Vec<ComponentPath> = self
+                    .state
+                    .components()
+                    .into_iter()
+                    .map(|component| ComponentPath {
+                        path: self.state.find(&component, term_size.into(), hints),
+                        component,
+                    })
+                    .collect();
+                components.extend(self.floating.components().into_iter().map(|component| {
+                    ComponentPath {
+                        component,
+                        path: None,
+                    }
+                }));
+
+                return Ok(ReplyResult::Components { components });
+            }
+            LayoutRequest::Batch { requests } => {
+                let backup = self.clone();
+
+                for (index, request) in requests.into_iter().enumerate() {
+                    // a query in a batch would have its reply replaced by the batch's
+                    let result = if request.is_query() {
+                        Err(LayoutError::QueryInBatch)
+                    } else {
+                        self.apply(request, term_size, hints, allocated)
+                    };
+
+                    if let Err(error) = result {
+                        *self = backup;
+                        return Err(LayoutError::BatchFailed {
+                            index,
+                            error: Box::new(error),
+                        });
+                    }
+                }
+            }
+        }
+
+        // nodes added without an id get a generated one
+        self.state.assign_ids(&mut self.next_id);
+
+        Ok(ReplyResult::Ok)
+    }
+
+    /// generate the tiled layout again, tabs keep showing the same components
+    /// and the zoom moves to the node containing the components that were zoomed
+    fn retile(&mut self, screen: Rect, hints: &SizeHints) {
+        let Some(tiled) = &self.tiling else {
+            return;
+        };
+
+        let zoomed_components = self
+            .zoomed
+            .as_ref()
+            .and_then(|at| self.state.resolve(at, screen, hints).ok())
+            .and_then(|at| self.state.get(&at))
+            .map(Layout::components);
+        let visible = self.state.visible();
+
+        self.state = tiled.layout();
+        self.state.show(&visible);
+        self.state.assign_ids(&mut self.next_id);
+
+        self.zoomed = zoomed_components
+            .and_then(|components| self.state.enclosing(&components, screen, hints))
+            .and_then(|at| self.state.get(&at))
+            .and_then(Layout::id)
+            .map(|id| Target::Id { id: id.to_string() });
+    }
+
+    /// remove a component from the tiled list, the tree and floating windows
+    fn drop_component(&mut self, component: &Discriminator, screen: Rect, hints: &SizeHints) {
+        if let Some(tiled) = &mut self.tiling {
+            if tiled.remove(component).is_ok() {
+                self.retile(screen, hints);
+            }
+        }
+
+        self.state.remove_component(component, screen, hints);
+        self.floating.remove_component(component, screen, hints);
+        self.watch.retain(|discrim| discrim != component);
+    }
+}
 
 #[tokio::main]
 async fn main() {
@@ -19,6 +348,14 @@ async fn main() {
         Subscription::specific_message_tag("!layout-add".to_string()).into(),
         Subscription::specific_message_tag("!layout-set".to_string()).into(),
         Subscription::specific_message_tag("!layout-remove".to_string()).into(),
+        Subscription::specific_message_tag("!layout-move".to_string()).into(),
+        Subscription::specific_message_tag("!layout-resize".to_string()).into(),
+        Subscription::specific_message_tag("!layout-tab".to_string()).into(),
+        Subscription::specific_message_tag("!layout-zoom".to_string()).into(),
+        Subscription::specific_message_tag("!layout-tiling".to_string()).into(),
+        Subscription::specific_message_tag("!layout-floating".to_string()).into(),
+        Subscription::specific_message_tag("!layout-query".to_string()).into(),
+        Subscription::specific_message_tag("!layout-batch".to_string()).into(),
         Subscription::ScreenResize.with_priority(100),
         Subscription::Focused.with_priority(50),
     ]);
@@ -29,7 +366,23 @@ async fn main() {
         Dimension::new(term_width, term_height)
     };
 
-    let mut state = Layout::None;
+    let mut daemon = Daemon::new();
+    // last area sent to each component
+    let mut allocated: HashMap<Discriminator, serde_json::Value> = HashMap::new();
+    // size hints set by components
+    let mut hints = SizeHints::new();
+
+    let timeout = std::env::var(TIMEOUT_VAR)
+        .ok()
+        .and_then(|timeout| timeout.parse().ok())
+        .unwrap_or(DEFAULT_TIMEOUT);
+    let timeout = (timeout != 0).then(|| Duration::from_millis(timeout));
+
+    let missed = std::env::var(MISSED_VAR)
+        .ok()
+        .and_then(|missed| missed.parse().ok())
+        .unwrap_or(DEFAULT_MISSED);
+    let mut confirmations = Confirmations::new(missed);
 
     let mut events_delayed: LinkedList<Event> = LinkedList::new();
 
@@ -47,75 +400,57 @@ async fn main() {
         };
 
         match event.get() {
-            EventVariant::Message { content, .. } => {
-                let content: LayoutRequest =
-                    if let Ok(req) = serde_json::from_value(content.clone()) {
-                        req
-                    } else {
-                        continue;
-                    };
-
-                match content {
-                    LayoutRequest::Add {
-                        at,
-                        split,
-                        constraint_1,
-                        constraint_2,
-                        component,
-                        border,
-                    } => {
-                        if !state.add(
-                            &at,
-                            &split,
-                            constraint_1,
-                            constraint_2,
-                            component.clone(),
-                            border,
-                        ) {
-                            continue;
-                        }
+            EventVariant::Message {
+                content, sender, ..
+            } => {
+                // read separately, so requests that fail to parse are still answered with it
+                let request_id = content.get("request_id").cloned();
 
-                        if let Some(component) = component {
-                            CLIENT
-                                .get()
-                                .unwrap()
-                                .watch(CONFIRM.to_string(), component)
-                                .await;
-                        }
-                    }
-                    LayoutRequest::Remove { at } => {
-                        if let Some(state) = state.get(&at) {
-                            let mut set = JoinSet::new();
-
-                            state.components().into_iter().for_each(|discrim| {
-                                set.spawn(
-                                    CLIENT.get().unwrap().watch(CONFIRM.to_string(), discrim),
-                                );
-                            });
-
-                            while set.join_next().await.is_some() {}
-                        } else {
-                            continue;
-                        }
+                let result = serde_json::from_value::<LayoutRequest>(content.clone())
+                    .map_err(|err| LayoutError::InvalidJson {
+                        message: err.to_string(),
+                    })
+                    .and_then(|request| daemon.apply(request, term_size, &hints, &allocated));
 
-                        if !state.remove(&at) {
-                            continue;
-                        }
-                    }
-                    LayoutRequest::SetLayout { at, layout } => {
-                        if !state.set(&at, layout) {
-                            continue;
-                        }
+                // queries and failed requests leave the layout as it is
+                let changed = matches!(result, Ok(ReplyResult::Ok));
 
-                        let mut set = JoinSet::new();
+                // the request is applied even if constraints do not fit, but the sender is told
+                let warnings = if changed {
+                    daemon
+                        .state
+                        .unsatisfiable(term_size.into(), &hints)
+                        .into_iter()
+                        .map(Warning::from)
+                        .collect()
+                } else {
+                    Vec::new()
+                };
 
-                        state.components().into_iter().for_each(|discrim| {
-                            set.spawn(CLIENT.get().unwrap().watch(CONFIRM.to_string(), discrim));
-                        });
+                CLIENT
+                    .get()
+                    .unwrap()
+                    .message(
+                        sender.clone(),
+                        serde_json::to_value(
+                            Reply::new(request_id, result.into()).with_warnings(warnings),
+                        )
+                        .unwrap(),
+                        REPLY.to_string(),
+                    )
+                    .await;
 
-                        while set.join_next().await.is_some() {}
-                    }
+                if !changed {
+                    continue;
                 }
+
+                let mut set = JoinSet::new();
+
+                daemon.watch.drain(..).for_each(|discrim| {
+                    set.spawn(CLIENT.get().unwrap().watch(CONFIRM.to_string(), discrim));
+                });
+
+                while set.join_next().await.is_some() {}
             }
             EventVariant::Focused => {
                 term_size = {
@@ -124,36 +459,116 @@ async fn main() {
                 }
             }
             EventVariant::Resize { width, height } => term_size = Dimension::new(*width, *height),
+            EventVariant::ValueUpdated {
+                label,
+                discrim,
+                new,
+                ..
+            } if label == HINT => match serde_json::from_value(new.clone()) {
+                Ok(hint) => {
+                    if hints.insert(discrim.clone(), hint) == Some(hint) {
+                        continue;
+                    }
+                }
+                Err(_) => {
+                    if hints.remove(discrim).is_none() {
+                        continue;
+                    }
+                }
+            },
             _ => continue,
         }
 
-        CLIENT.get().unwrap().clear_all();
+        // render again until no component is dropped
+        loop {
+            CLIENT.get().unwrap().clear_all();
+
+            let zoomed_at = daemon
+                .zoomed
+                .as_ref()
+                .and_then(|at| daemon.state.resolve(at, term_size.into(), &hints).ok());
+
+            // the zoomed node is gone, go back to the normal layout
+            if zoomed_at.is_none() {
+                daemon.zoomed = None;
+            }
+
+            let mut areas = match zoomed_at {
+                Some(at) => {
+                    daemon
+                        .state
+                        .zoomed_areas(&at, term_size.into(), CLIENT.get().unwrap(), &hints)
+                }
+                None => daemon
+                    .state
+                    .areas(term_size.into(), CLIENT.get().unwrap(), &hints),
+            };
+            // floating windows are drawn last so they cover the tiled layout
+            areas.extend(
+                daemon
+                    .floating
+                    .areas(term_size.into(), CLIENT.get().unwrap(), &hints),
+            );
 
-        let areas = state.areas(term_size.into(), CLIENT.get().unwrap());
+            CLIENT.get().unwrap().renderall().await;
+            let mut set = JoinSet::new();
+            let mut watches = JoinSet::new();
+            let mut unconfirmed = HashSet::new();
 
-        CLIENT.get().unwrap().renderall().await;
-        let mut set = JoinSet::new();
-        let mut unconfirmed = HashSet::new();
+            let areas: HashMap<Discriminator, serde_json::Value> = areas
+                .into_iter()
+                .map(|(rect, discrim)| (discrim, serde_json::to_value(rect).unwrap()))
+                .collect();
 
-        areas.into_iter().for_each(|(rect, discrim)| {
-            set.spawn(CLIENT.get().unwrap().set(
-                ALLOCATED.to_string(),
-                discrim.clone(),
-                serde_json::to_value(rect).unwrap(),
-            ));
-            unconfirmed.insert(discrim);
-        });
+            // only components with a changed area have to re-render,
+            // components that failed to confirm are asked again
+            areas.iter().for_each(|(discrim, rect)| {
+                if allocated.get(discrim) != Some(rect) || confirmations.is_pending(discrim) {
+                    set.spawn(CLIENT.get().unwrap().set(
+                        ALLOCATED.to_string(),
+                        discrim.clone(),
+                        rect.clone(),
+                    ));
+                    unconfirmed.insert(discrim.clone());
+                }
 
-        while set.join_next().await.is_some() {}
+                // newly placed components may publish size hints
+                if !allocated.contains_key(discrim) {
+                    watches.spawn(
+                        CLIENT
+                            .get()
+                            .unwrap()
+                            .watch(HINT.to_string(), discrim.clone()),
+                    );
+                }
+            });
+
+            // hints of components that left the layout no longer apply
+            hints.retain(|discrim, _| areas.contains_key(discrim));
+            let changed = LayoutChanged::new(&allocated, &areas);
+            allocated = areas;
+
+            while set.join_next().await.is_some() {}
+            while watches.join_next().await.is_some() {}
+
+            let waited = unconfirmed.clone();
+            let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+            while !unconfirmed.is_empty() {
+                let event = match deadline {
+                    Some(deadline) => {
+                        match time::timeout_at(deadline, CLIENT.get().unwrap().recv()).await {
+                            Ok(event) => event,
+                            // whatever is left failed to confirm
+                            Err(_) => break,
+                        }
+                    }
+                    None => CLIENT.get().unwrap().recv().await,
+                };
 
-        if !unconfirmed.is_empty() {
-            loop {
-                let event = CLIENT.get().unwrap().recv().await;
                 if let EventVariant::ValueUpdated { label, discrim, .. } = event.get() {
                     if label == CONFIRM {
-                        if unconfirmed.remove(discrim) && unconfirmed.is_empty() {
-                            break;
-                        }
+                        unconfirmed.remove(discrim);
                     } else {
                         events_delayed.push_back(event);
                     }
@@ -161,6 +576,41 @@ async fn main() {
                     events_delayed.push_back(event);
                 }
             }
+
+            if !changed.is_empty() {
+                CLIENT
+                    .get()
+                    .unwrap()
+                    .broadcast(serde_json::to_value(changed).unwrap(), CHANGED.to_string())
+                    .await;
+            }
+
+            confirmations.retain(|discrim| allocated.contains_key(discrim));
+            let failed = confirmations.record(&waited, &unconfirmed);
+
+            if failed.is_empty() {
+                break;
+            }
+
+            // components that keep failing to confirm are treated as exited
+            failed
+                .dropped
+                .iter()
+                .for_each(|discrim| daemon.drop_component(discrim, term_size.into(), &hints));
+            let dropped = !failed.dropped.is_empty();
+
+            CLIENT
+                .get()
+                .unwrap()
+                .broadcast(
+                    serde_json::to_value(failed).unwrap(),
+                    CONFIRM_FAILED.to_string(),
+                )
+                .await;
+
+            if !dropped {
+                break;
+            }
         }
 
         event.done(true);
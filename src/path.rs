@@ -0,0 +1,64 @@
+use libccanvas::{bindings::Discriminator, features::common::Direction};
+use serde::{Deserialize, Serialize};
+
+use crate::Orientation;
+
+/// a single step in a path from the root of a layout
+///
+/// binary splits are addressed by direction (or index 0/1),
+/// n-ary splits are addressed by index
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(untagged)]
+pub enum Step {
+    Direction(Direction),
+    Index(usize),
+}
+
+impl Step {
+    /// index of the child this step points to in a split of the given orientation
+    pub(crate) fn index(&self, orientation: Orientation) -> Option<usize> {
+        match (self, orientation) {
+            (Self::Index(index), _) => Some(*index),
+            (Self::Direction(Direction::Left), Orientation::Horizontal)
+            | (Self::Direction(Direction::Up), Orientation::Vertical) => Some(0),
+            (Self::Direction(Direction::Right), Orientation::Horizontal)
+            | (Self::Direction(Direction::Down), Orientation::Vertical) => Some(1),
+            _ => None,
+        }
+    }
+}
+
+impl From<Direction> for Step {
+    fn from(value: Direction) -> Self {
+        Self::Direction(value)
+    }
+}
+
+impl From<usize> for Step {
+    fn from(value: usize) -> Self {
+        Self::Index(value)
+    }
+}
+
+/// a node in the layout, by path, by id, or as the pane containing a component
+#[derive(Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(untagged)]
+pub enum Target {
+    Path(Vec<Step>),
+    Id { id: String },
+    Component { component: Discriminator },
+}
+
+impl From<Vec<Step>> for Target {
+    fn from(value: Vec<Step>) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<Discriminator> for Target {
+    fn from(value: Discriminator) -> Self {
+        Self::Component { component: value }
+    }
+}
@@ -0,0 +1,132 @@
+use libccanvas::bindings::Discriminator;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{LayoutError, Step, Unsatisfiable};
+
+/// answer to a request, sent back to its sender
+#[derive(Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Reply {
+    /// the id the request was sent with, if any
+    pub request_id: Option<Value>,
+    #[serde(flatten)]
+    pub result: ReplyResult,
+    /// problems with the layout the request left, which was still applied
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(tag = "result")]
+pub enum ReplyResult {
+    /// the layout is updated
+    #[serde(rename = "ok")]
+    Ok,
+    #[serde(rename = "error")]
+    Error { error: LayoutError },
+    /// a serialized subtree
+    #[serde(rename = "layout")]
+    Layout { layout: Value },
+    #[serde(rename = "areas")]
+    Areas { areas: Vec<Area> },
+    #[serde(rename = "components")]
+    Components { components: Vec<ComponentPath> },
+}
+
+/// a problem with the layout that does not stop a request from being applied
+#[derive(Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(tag = "type")]
+pub enum Warning {
+    /// the constraints of the split or grid at `path` do not fit, so its children are shrunk
+    #[serde(rename = "unsatisfiable")]
+    Unsatisfiable {
+        path: Vec<Step>,
+        required: u32,
+        available: u32,
+    },
+}
+
+/// the area allocated to a component
+#[derive(Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Area {
+    pub component: Discriminator,
+    pub rect: Value,
+}
+
+/// where a component is in the layout
+#[derive(Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ComponentPath {
+    pub component: Discriminator,
+    /// none for components in floating windows
+    pub path: Option<Vec<Step>>,
+}
+
+impl Reply {
+    pub fn new(request_id: Option<Value>, result: ReplyResult) -> Self {
+        Self {
+            request_id,
+            result,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<Warning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
+
+impl From<(Vec<Step>, Unsatisfiable)> for Warning {
+    fn from((path, unsatisfiable): (Vec<Step>, Unsatisfiable)) -> Self {
+        Self::Unsatisfiable {
+            path,
+            required: unsatisfiable.required,
+            available: unsatisfiable.available,
+        }
+    }
+}
+
+impl From<Result<ReplyResult, LayoutError>> for ReplyResult {
+    fn from(value: Result<ReplyResult, LayoutError>) -> Self {
+        match value {
+            Ok(result) => result,
+            Err(error) => Self::Error { error },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn warnings_are_only_sent_if_any() {
+        let reply = Reply::new(Some(json!(7)), ReplyResult::Ok);
+        assert_eq!(
+            serde_json::to_value(reply).unwrap(),
+            json!({ "request_id": 7, "result": "ok" })
+        );
+
+        let unsatisfiable = Unsatisfiable {
+            required: 120,
+            available: 100,
+            lengths: vec![50, 50],
+        };
+        let reply = Reply::new(None, ReplyResult::Ok).with_warnings(vec![(
+            vec![Step::Index(1)],
+            unsatisfiable,
+        )
+            .into()]);
+        assert_eq!(
+            serde_json::to_value(reply).unwrap()["warnings"],
+            json!([{ "type": "unsatisfiable", "path": [1], "required": 120, "available": 100 }])
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use libccanvas::{bindings::Discriminator, features::common::Direction};
 use serde::Deserialize;
 
-use crate::{Border, Constraint, Layout};
+use crate::{Border, Constraint, Floating, Layout, Orientation, Target, Tiling, Unit};
 
 #[derive(Deserialize)]
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -9,15 +9,117 @@ use crate::{Border, Constraint, Layout};
 pub enum LayoutRequest {
     #[serde(rename = "add")]
     Add {
-        at: Vec<Direction>,
+        at: Target,
         split: Direction,
         constraint_1: Constraint,
         constraint_2: Constraint,
         component: Option<Discriminator>,
         border: Option<Border>,
+        /// id of the newly added node
+        id: Option<String>,
     },
     #[serde(rename = "remove")]
-    Remove { at: Vec<Direction> },
+    Remove { at: Target },
     #[serde(rename = "setlayout")]
-    SetLayout { at: Vec<Direction>, layout: Layout },
+    SetLayout { at: Target, layout: Layout },
+    #[serde(rename = "swap")]
+    Swap { a: Target, b: Target },
+    #[serde(rename = "move")]
+    Move {
+        from: Target,
+        to: Target,
+        split: Direction,
+        constraint_1: Constraint,
+        constraint_2: Constraint,
+    },
+    /// grow or shrink a pane relative to its current size
+    #[serde(rename = "resize")]
+    Resize {
+        at: Target,
+        orientation: Orientation,
+        delta: i32,
+        #[serde(default)]
+        unit: Unit,
+        /// smallest size in cells either pane can shrink to, defaults to 1
+        min: Option<u32>,
+    },
+    /// set the size of a pane
+    #[serde(rename = "resizeto")]
+    ResizeTo {
+        at: Target,
+        orientation: Orientation,
+        size: u32,
+        #[serde(default)]
+        unit: Unit,
+        /// smallest size in cells either pane can shrink to, defaults to 1
+        min: Option<u32>,
+    },
+    /// zoom the node to fill the screen, or restore the layout if something is zoomed
+    #[serde(rename = "togglezoom")]
+    ToggleZoom { at: Target },
+    #[serde(rename = "settab")]
+    SetTab { at: Target, index: usize },
+    #[serde(rename = "nexttab")]
+    NextTab { at: Target },
+    #[serde(rename = "prevtab")]
+    PrevTab { at: Target },
+    /// generate the layout from the list of components with an algorithm,
+    /// starting with the components currently in the layout,
+    /// or stop doing so if none
+    #[serde(rename = "settiling")]
+    SetTiling {
+        algorithm: Option<Tiling>,
+        border: Option<Border>,
+    },
+    /// add a component to the tiled list, at the end if no index is given
+    #[serde(rename = "tileadd")]
+    TileAdd {
+        component: Discriminator,
+        index: Option<usize>,
+    },
+    #[serde(rename = "tileremove")]
+    TileRemove { component: Discriminator },
+    #[serde(rename = "addfloating")]
+    AddFloating(Floating),
+    #[serde(rename = "movefloating")]
+    MoveFloating {
+        id: String,
+        x: Option<Constraint>,
+        y: Option<Constraint>,
+    },
+    #[serde(rename = "resizefloating")]
+    ResizeFloating {
+        id: String,
+        width: Constraint,
+        height: Constraint,
+    },
+    #[serde(rename = "closefloating")]
+    CloseFloating { id: String },
+    #[serde(rename = "raisefloating")]
+    RaiseFloating { id: String },
+    #[serde(rename = "lowerfloating")]
+    LowerFloating { id: String },
+    /// reply with the node at the target
+    #[serde(rename = "getlayout")]
+    GetLayout { at: Target },
+    /// reply with the area last allocated to each component
+    #[serde(rename = "getareas")]
+    GetAreas,
+    /// reply with every component and where it is in the layout
+    #[serde(rename = "listcomponents")]
+    ListComponents,
+    /// apply the requests in order and render once,
+    /// if any of them fails none of them are applied, queries cannot be batched
+    #[serde(rename = "batch")]
+    Batch { requests: Vec<LayoutRequest> },
+}
+
+impl LayoutRequest {
+    /// whether the request only reads the state, and is answered with it
+    pub fn is_query(&self) -> bool {
+        matches!(
+            self,
+            Self::GetLayout { .. } | Self::GetAreas | Self::ListComponents
+        )
+    }
 }
@@ -0,0 +1,497 @@
+use std::fmt;
+
+use crate::{Constraint, ConstraintVariant, HintedSize};
+
+/// constraints of a split that cannot all fit in the available length
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Unsatisfiable {
+    /// smallest total length the constraints allow
+    pub required: u32,
+    pub available: u32,
+    /// best effort lengths, still exactly tiling the available length
+    pub lengths: Vec<u32>,
+}
+
+impl fmt::Display for Unsatisfiable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraints require at least {} cells, but only {} are available",
+            self.required, self.available
+        )
+    }
+}
+
+/// how strongly a constraint holds on to its preferred length,
+/// weaker constraints give up space first and take leftover space first
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Strength {
+    Fill,
+    Min,
+    Max,
+    Percentage,
+    Auto,
+    Length,
+}
+
+#[derive(Clone)]
+struct Item {
+    strength: Strength,
+    min: u32,
+    preferred: u32,
+    max: u32,
+    /// share of leftover space for fills
+    weight: u32,
+    hidden: bool,
+}
+
+impl Item {
+    /// `hinted` is the preferred size hinted for the child, used by auto constraints
+    fn new(constraint: &Constraint, length: u32, hinted: Option<u32>) -> Self {
+        let preferred = constraint.eval(length);
+
+        let (strength, min, preferred, max, weight) = match constraint.base() {
+            ConstraintVariant::Fill { weight } => (Strength::Fill, 0, 0, u32::MAX, *weight),
+            ConstraintVariant::Auto => match hinted {
+                Some(hinted) => {
+                    let preferred = constraint.eval_from(hinted);
+                    (Strength::Auto, 0, preferred, preferred, 0)
+                }
+                None => (Strength::Fill, 0, 0, u32::MAX, 1),
+            },
+            ConstraintVariant::Min { value } => (
+                Strength::Min,
+                *value,
+                preferred.max(*value),
+                length.max(*value),
+                0,
+            ),
+            ConstraintVariant::Max { .. } => (Strength::Max, 0, preferred, preferred, 0),
+            ConstraintVariant::Percentage { .. } | ConstraintVariant::Ratio { .. } => {
+                (Strength::Percentage, 0, preferred, length, 0)
+            }
+            ConstraintVariant::Clamp { base, min, max } => {
+                let inner = Self::new(base, length, hinted);
+                let lower = min.unwrap_or(0);
+                let upper = max.unwrap_or(u32::MAX).max(lower);
+                // the offsets of the clamp itself apply to the clamped lengths,
+                // an unbounded maximum stays unbounded
+                let offset = |value: u32| {
+                    if value == u32::MAX {
+                        value
+                    } else {
+                        constraint.eval_from(value)
+                    }
+                };
+
+                (
+                    inner.strength,
+                    offset(inner.min.clamp(lower, upper)),
+                    offset(inner.preferred.clamp(lower, upper)),
+                    offset(inner.max.clamp(lower, upper)),
+                    inner.weight,
+                )
+            }
+            ConstraintVariant::Length { .. } => {
+                // evaluated without a limit, a length that does not fit is unsatisfiable
+                let preferred = constraint.eval(u32::MAX);
+                (Strength::Length, preferred, preferred, preferred, 0)
+            }
+        };
+
+        Self {
+            strength,
+            min,
+            preferred,
+            max,
+            weight,
+            hidden: false,
+        }
+    }
+
+    /// the item kept at or above `minimum`, or taking up no space at all if hidden
+    fn at_least(&self, minimum: u32, hidden: bool) -> Self {
+        if hidden {
+            return Self {
+                strength: Strength::Length,
+                min: 0,
+                preferred: 0,
+                max: 0,
+                weight: 0,
+                hidden: true,
+            };
+        }
+
+        Self {
+            min: self.min.max(minimum),
+            preferred: self.preferred.max(minimum),
+            max: self.max.max(minimum),
+            ..self.clone()
+        }
+    }
+}
+
+/// lengths of all children of a split, satisfying their constraints together
+///
+/// starting from the preferred lengths, weaker constraints shrink towards their minimum
+/// until everything fits, then leftover space goes to fills by weight, then to other
+/// constraints that can grow, and finally to the last child, so the lengths always add
+/// up to exactly `length`
+pub fn solve(constraints: &[&Constraint], length: u32) -> Result<Vec<u32>, Unsatisfiable> {
+    solve_items(
+        constraints
+            .iter()
+            .map(|constraint| Item::new(constraint, length, None))
+            .collect(),
+        length,
+    )
+}
+
+/// like `solve`, but auto constraints take the preferred size of their child,
+/// and every child is also kept at or above its minimum length
+///
+/// siblings shrink first, only if the minimums cannot all fit are children hidden
+/// by giving them no length, starting from the last child
+pub fn solve_with_hints(
+    constraints: &[&Constraint],
+    sizes: &[HintedSize],
+    length: u32,
+) -> Result<Vec<u32>, Unsatisfiable> {
+    let size = |index: usize| sizes.get(index).copied().unwrap_or_default();
+    let items: Vec<Item> = constraints
+        .iter()
+        .enumerate()
+        .map(|(index, constraint)| Item::new(constraint, length, size(index).preferred))
+        .collect();
+
+    // if the constraints alone do not fit, hiding children would not help
+    let fallback = solve_items(items.clone(), length)?;
+    let mut hidden = vec![false; items.len()];
+
+    loop {
+        let with_minimums = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| item.at_least(size(index).min, hidden[index]))
+            .collect();
+
+        if let Ok(lengths) = solve_items(with_minimums, length) {
+            return Ok(lengths);
+        }
+
+        match (0..items.len())
+            .rev()
+            .find(|index| !hidden[*index] && size(*index).min > 0)
+        {
+            Some(index) => hidden[index] = true,
+            None => return Ok(fallback),
+        }
+    }
+}
+
+fn solve_items(items: Vec<Item>, length: u32) -> Result<Vec<u32>, Unsatisfiable> {
+    let mut lengths: Vec<u32> = items.iter().map(|item| item.preferred).collect();
+
+    let total: u64 = lengths.iter().map(|length| *length as u64).sum();
+
+    if total > length as u64 {
+        let mut excess = total - length as u64;
+
+        for strength in [
+            Strength::Min,
+            Strength::Max,
+            Strength::Percentage,
+            Strength::Auto,
+        ] {
+            let floors: Vec<u32> = items
+                .iter()
+                .zip(&lengths)
+                .map(|(item, length)| {
+                    if item.strength == strength {
+                        item.min.min(*length)
+                    } else {
+                        *length
+                    }
+                })
+                .collect();
+            excess = shrink(&mut lengths, excess, &floors);
+        }
+
+        if excess > 0 {
+            let required = lengths.iter().copied().fold(0, u32::saturating_add);
+            shrink(&mut lengths, excess, &vec![0; items.len()]);
+
+            return Err(Unsatisfiable {
+                required,
+                available: length,
+                lengths,
+            });
+        }
+    } else {
+        let mut leftover = (length as u64 - total) as u32;
+
+        let fills: Vec<(usize, u32, u64)> = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.strength == Strength::Fill)
+            .map(|(index, item)| {
+                let capacity = item.max.saturating_sub(lengths[index]);
+                (index, capacity, item.weight as u64)
+            })
+            .collect();
+        leftover = grow(&mut lengths, leftover, &fills);
+
+        for strength in [Strength::Min, Strength::Percentage] {
+            let slots: Vec<(usize, u32, u64)> = items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| item.strength == strength)
+                .map(|(index, item)| {
+                    let capacity = item.max.saturating_sub(lengths[index]);
+                    (index, capacity, item.preferred.max(1) as u64)
+                })
+                .collect();
+            leftover = grow(&mut lengths, leftover, &slots);
+        }
+
+        if let Some(last) = items.iter().rposition(|item| !item.hidden) {
+            lengths[last] += leftover;
+        } else if let Some(last) = lengths.last_mut() {
+            *last += leftover;
+        }
+    }
+
+    Ok(lengths)
+}
+
+/// take `excess` away, proportionally to how far each length is above its floor,
+/// returns what could not be taken
+fn shrink(lengths: &mut [u32], excess: u64, floors: &[u32]) -> u64 {
+    let slots: Vec<(u32, u64)> = lengths
+        .iter()
+        .zip(floors)
+        .map(|(length, floor)| {
+            let slack = length.saturating_sub(*floor);
+            (slack, slack as u64)
+        })
+        .collect();
+
+    let amount = excess.min(u32::MAX as u64) as u32;
+    let (taken, left) = distribute(amount, &slots);
+
+    lengths
+        .iter_mut()
+        .zip(taken)
+        .for_each(|(length, taken)| *length -= taken);
+
+    excess - (amount - left) as u64
+}
+
+/// give `amount` to the `(index, capacity, weight)` slots, returns what is left over
+fn grow(lengths: &mut [u32], amount: u32, slots: &[(usize, u32, u64)]) -> u32 {
+    let (given, left) = distribute(
+        amount,
+        &slots
+            .iter()
+            .map(|(_, capacity, weight)| (*capacity, *weight))
+            .collect::<Vec<_>>(),
+    );
+
+    slots
+        .iter()
+        .zip(given)
+        .for_each(|((index, _, _), given)| lengths[*index] += given);
+
+    left
+}
+
+/// spread `amount` over `(capacity, weight)` slots proportionally to their weight
+/// without going over capacity, returns the amount given to each slot and what is left
+fn distribute(mut amount: u32, slots: &[(u32, u64)]) -> (Vec<u32>, u32) {
+    let mut given = vec![0; slots.len()];
+
+    loop {
+        let open: Vec<usize> = (0..slots.len())
+            .filter(|index| given[*index] < slots[*index].0 && slots[*index].1 > 0)
+            .collect();
+
+        if amount == 0 || open.is_empty() {
+            return (given, amount);
+        }
+
+        let total: u64 = open.iter().map(|index| slots[*index].1).sum();
+        let mut spent = 0;
+
+        open.iter().for_each(|index| {
+            let (capacity, weight) = slots[*index];
+            let share = ((amount as u64 * weight / total) as u32).min(capacity - given[*index]);
+            given[*index] += share;
+            spent += share;
+        });
+
+        // every share rounded down to nothing, hand out single cells in order
+        if spent == 0 {
+            open.iter().take(amount as usize).for_each(|index| {
+                given[*index] += 1;
+                spent += 1;
+            });
+        }
+
+        amount -= spent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lengths(constraints: &[ConstraintVariant], length: u32) -> Vec<u32> {
+        let constraints: Vec<Constraint> =
+            constraints.iter().cloned().map(Constraint::from).collect();
+        let constraints: Vec<&Constraint> = constraints.iter().collect();
+
+        solve(&constraints, length).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    #[test]
+    fn tiles_exactly() {
+        for length in [19, 33, 100, 241] {
+            let solved = lengths(
+                &[
+                    ConstraintVariant::length(10),
+                    ConstraintVariant::percentage(50),
+                    ConstraintVariant::min(5),
+                    ConstraintVariant::max(30),
+                ],
+                length,
+            );
+            assert_eq!(solved.iter().sum::<u32>(), length);
+        }
+
+        let third = ConstraintVariant::percentage(33);
+        assert_eq!(
+            lengths(&[third.clone(), third.clone(), third], 100),
+            vec![34, 33, 33]
+        );
+
+        let third = ConstraintVariant::ratio(1, 3);
+        assert_eq!(
+            lengths(&[third.clone(), third.clone(), third], 100),
+            vec![34, 33, 33]
+        );
+    }
+
+    #[test]
+    fn weaker_constraints_shrink_first() {
+        assert_eq!(
+            lengths(
+                &[
+                    ConstraintVariant::percentage(60),
+                    ConstraintVariant::length(50)
+                ],
+                100
+            ),
+            vec![50, 50]
+        );
+        assert_eq!(
+            lengths(
+                &[ConstraintVariant::max(80), ConstraintVariant::length(30)],
+                100
+            ),
+            vec![70, 30]
+        );
+    }
+
+    #[test]
+    fn clamp_keeps_its_offsets() {
+        let five = || Some(ConstraintVariant::length(5).into());
+        let clamp = |offset_pos, offset_neg| {
+            Constraint::new(
+                ConstraintVariant::clamp(
+                    ConstraintVariant::percentage(30).into(),
+                    Some(20),
+                    Some(80),
+                ),
+                offset_pos,
+                offset_neg,
+            )
+        };
+        let lengths = |clamp: Constraint, length| {
+            let fill = ConstraintVariant::fill(1).into();
+            solve(&[&clamp, &fill], length).unwrap_or_else(|err| panic!("{err}"))
+        };
+
+        assert_eq!(lengths(clamp(five(), None), 100), vec![35, 65]);
+        assert_eq!(lengths(clamp(five(), None), 40), vec![25, 15]);
+        assert_eq!(lengths(clamp(None, five()), 400), vec![75, 325]);
+    }
+
+    #[test]
+    fn unsatisfiable_lengths_still_tile() {
+        let constraints: Vec<Constraint> = vec![
+            ConstraintVariant::length(60).into(),
+            ConstraintVariant::length(60).into(),
+        ];
+        let constraints: Vec<&Constraint> = constraints.iter().collect();
+
+        let err = solve(&constraints, 100).expect_err("lengths do not fit");
+        assert_eq!(err.required, 120);
+        assert_eq!(err.lengths.iter().sum::<u32>(), 100);
+    }
+
+    #[test]
+    fn hints_keep_minimums() {
+        let constraints: Vec<Constraint> = vec![
+            ConstraintVariant::percentage(80).into(),
+            ConstraintVariant::percentage(20).into(),
+        ];
+        let constraints: Vec<&Constraint> = constraints.iter().collect();
+        let sizes = [
+            HintedSize::default(),
+            HintedSize {
+                min: 30,
+                preferred: None,
+            },
+        ];
+
+        let solved =
+            solve_with_hints(&constraints, &sizes, 100).unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(solved, vec![70, 30]);
+
+        // both minimums cannot fit, so the last child is hidden
+        let sizes = [
+            HintedSize {
+                min: 60,
+                preferred: None,
+            },
+            HintedSize {
+                min: 60,
+                preferred: None,
+            },
+        ];
+        let solved =
+            solve_with_hints(&constraints, &sizes, 100).unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(solved, vec![100, 0]);
+    }
+
+    #[test]
+    fn auto_takes_the_preferred_size() {
+        let constraints: Vec<Constraint> = vec![
+            ConstraintVariant::fill(1).into(),
+            ConstraintVariant::auto().into(),
+        ];
+        let constraints: Vec<&Constraint> = constraints.iter().collect();
+        let sizes = [
+            HintedSize::default(),
+            HintedSize {
+                min: 0,
+                preferred: Some(3),
+            },
+        ];
+
+        let solved =
+            solve_with_hints(&constraints, &sizes, 40).unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(solved, vec![37, 3]);
+    }
+}
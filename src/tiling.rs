@@ -0,0 +1,312 @@
+use libccanvas::bindings::Discriminator;
+use serde::Deserialize;
+
+use crate::{Border, Constraint, ConstraintVariant, Layout, LayoutError, Orientation, Tab};
+
+/// algorithm used to generate a layout from a list of components
+#[derive(Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(tag = "type")]
+pub enum Tiling {
+    /// `masters` components stacked on the left taking up `ratio` percent,
+    /// the rest stacked on the right
+    #[serde(rename = "master stack")]
+    MasterStack {
+        #[serde(default = "default_ratio")]
+        ratio: u32,
+        #[serde(default = "default_masters")]
+        masters: usize,
+    },
+    /// each component takes half of the space left, splitting right then down
+    #[serde(rename = "dwindle")]
+    Dwindle,
+    /// each component takes half of the space left, rotating clockwise
+    #[serde(rename = "spiral")]
+    Spiral,
+    /// rows of equally sized components
+    #[serde(rename = "grid")]
+    Grid,
+    /// one component at a time, as tabs
+    #[serde(rename = "monocle")]
+    Monocle,
+    /// the first component in the middle taking up `ratio` percent,
+    /// the rest stacked on both sides
+    #[serde(rename = "centered master")]
+    CenteredMaster {
+        #[serde(default = "default_ratio")]
+        ratio: u32,
+    },
+}
+
+fn default_ratio() -> u32 {
+    50
+}
+
+fn default_masters() -> usize {
+    1
+}
+
+/// layout derived from an ordered list of components
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Tiled {
+    pub algorithm: Tiling,
+    pub components: Vec<Discriminator>,
+    pub border: Option<Border>,
+}
+
+impl Tiled {
+    pub fn new(algorithm: Tiling, components: Vec<Discriminator>, border: Option<Border>) -> Self {
+        Self {
+            algorithm,
+            components,
+            border,
+        }
+    }
+
+    /// add a component at the index, or at the end if none
+    pub fn add(&mut self, component: Discriminator, index: Option<usize>) {
+        let index = index
+            .unwrap_or(self.components.len())
+            .min(self.components.len());
+        self.components.insert(index, component);
+    }
+
+    /// remove a component
+    pub fn remove(&mut self, component: &Discriminator) -> Result<(), LayoutError> {
+        let len = self.components.len();
+        self.components.retain(|discrim| discrim != component);
+
+        if len == self.components.len() {
+            return Err(LayoutError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// generate the layout
+    pub fn layout(&self) -> Layout {
+        let mut panes: Vec<Layout> = self
+            .components
+            .iter()
+            .map(|discrim| Layout::single(Some(discrim.clone()), self.border.clone()))
+            .collect();
+
+        if panes.len() < 2 {
+            return panes.pop().unwrap_or_default();
+        }
+
+        match &self.algorithm {
+            Tiling::MasterStack { ratio, masters } => {
+                if panes.len() <= *masters {
+                    return stack(Orientation::Vertical, panes);
+                }
+
+                let rest = panes.split_off((*masters).max(1));
+
+                Layout::split(
+                    Orientation::Horizontal,
+                    vec![
+                        (percentage(*ratio), stack(Orientation::Vertical, panes)),
+                        (fill(), stack(Orientation::Vertical, rest)),
+                    ],
+                )
+            }
+            Tiling::CenteredMaster { ratio } => {
+                let mut panes = panes.into_iter();
+                let master = panes.next().unwrap();
+                let (right, left): (Vec<_>, Vec<_>) =
+                    panes.enumerate().partition(|(index, _)| index % 2 == 0);
+                let right = right.into_iter().map(|(_, pane)| pane).collect();
+                let left: Vec<Layout> = left.into_iter().map(|(_, pane)| pane).collect();
+
+                if left.is_empty() {
+                    return Layout::split(
+                        Orientation::Horizontal,
+                        vec![
+                            (percentage(*ratio), master),
+                            (fill(), stack(Orientation::Vertical, right)),
+                        ],
+                    );
+                }
+
+                Layout::split(
+                    Orientation::Horizontal,
+                    vec![
+                        (
+                            percentage((100 - (*ratio).min(100)) / 2),
+                            stack(Orientation::Vertical, left),
+                        ),
+                        (percentage(*ratio), master),
+                        (fill(), stack(Orientation::Vertical, right)),
+                    ],
+                )
+            }
+            Tiling::Dwindle => dwindle(panes, 0, false),
+            Tiling::Spiral => dwindle(panes, 0, true),
+            Tiling::Grid => {
+                let columns = (panes.len() as f32).sqrt().ceil() as usize;
+                let mut rows = Vec::new();
+
+                while !panes.is_empty() {
+                    let rest = panes.split_off(columns.min(panes.len()));
+                    rows.push(stack(Orientation::Horizontal, panes));
+                    panes = rest;
+                }
+
+                stack(Orientation::Vertical, rows)
+            }
+            Tiling::Monocle => Layout::tabs(
+                panes
+                    .into_iter()
+                    .map(|pane| Tab::new(String::new(), pane))
+                    .collect(),
+                self.border.clone(),
+            ),
+        }
+    }
+}
+
+fn percentage(value: u32) -> Constraint {
+    ConstraintVariant::percentage(value).into()
+}
+
+fn fill() -> Constraint {
+    ConstraintVariant::fill(1).into()
+}
+
+/// equally sized layouts one after another
+fn stack(orientation: Orientation, mut layouts: Vec<Layout>) -> Layout {
+    if layouts.len() == 1 {
+        return layouts.pop().unwrap();
+    }
+
+    Layout::split(
+        orientation,
+        layouts.into_iter().map(|layout| (fill(), layout)).collect(),
+    )
+}
+
+/// first layout takes half of the space, the rest dwindle in the other half,
+/// alternating between horizontal and vertical splits
+///
+/// spirals place the first layout on the right or bottom every other split
+fn dwindle(mut layouts: Vec<Layout>, depth: usize, spiral: bool) -> Layout {
+    if layouts.len() == 1 {
+        return layouts.pop().unwrap();
+    }
+
+    let first = layouts.remove(0);
+    let rest = dwindle(layouts, depth + 1, spiral);
+
+    match (depth % 4, spiral) {
+        (0, _) | (2, false) => Layout::horizontal(first, rest, percentage(50), fill()),
+        (1, _) | (3, false) => Layout::vertical(first, rest, percentage(50), fill()),
+        (2, true) => Layout::horizontal(rest, first, percentage(50), fill()),
+        _ => Layout::vertical(rest, first, percentage(50), fill()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(n: u32) -> Discriminator {
+        serde_json::from_value(serde_json::json!([n])).unwrap()
+    }
+
+    fn tiled(algorithm: Tiling, count: u32) -> Layout {
+        Tiled::new(algorithm, (1..=count).map(component).collect(), None).layout()
+    }
+
+    /// the layout written out, with splits as `h(..)`, `v(..)` and `tabs(..)`
+    fn shape(layout: &Layout) -> String {
+        let children =
+            |layouts: Vec<&Layout>| layouts.into_iter().map(shape).collect::<Vec<_>>().join(",");
+
+        match layout {
+            Layout::None => String::new(),
+            Layout::Single { discrim, .. } => (1..10)
+                .find(|n| discrim.as_ref() == Some(&component(*n)))
+                .map_or(String::new(), |n| n.to_string()),
+            Layout::SplitHorizontal { left, right, .. } => {
+                format!("h({})", children(vec![left, right]))
+            }
+            Layout::SplitVertical { top, bottom, .. } => {
+                format!("v({})", children(vec![top, bottom]))
+            }
+            Layout::Split {
+                orientation,
+                children: split,
+                ..
+            } => format!(
+                "{}({})",
+                match orientation {
+                    Orientation::Horizontal => "h",
+                    Orientation::Vertical => "v",
+                },
+                children(split.iter().map(|(_, child)| child).collect())
+            ),
+            Layout::Tabs { tabs, .. } => {
+                format!(
+                    "tabs({})",
+                    children(tabs.iter().map(|tab| &tab.layout).collect())
+                )
+            }
+            _ => String::from("?"),
+        }
+    }
+
+    #[test]
+    fn master_stack() {
+        let algorithm = Tiling::MasterStack {
+            ratio: 60,
+            masters: 1,
+        };
+        assert_eq!(shape(&tiled(algorithm.clone(), 3)), "h(1,v(2,3))");
+        assert_eq!(shape(&tiled(algorithm, 1)), "1");
+
+        let algorithm = Tiling::MasterStack {
+            ratio: 60,
+            masters: 2,
+        };
+        assert_eq!(shape(&tiled(algorithm.clone(), 4)), "h(v(1,2),v(3,4))");
+        assert_eq!(shape(&tiled(algorithm, 2)), "v(1,2)");
+    }
+
+    #[test]
+    fn dwindle_and_spiral() {
+        assert_eq!(shape(&tiled(Tiling::Dwindle, 4)), "h(1,v(2,h(3,4)))");
+        assert_eq!(shape(&tiled(Tiling::Spiral, 4)), "h(1,v(2,h(4,3)))");
+        assert_eq!(shape(&tiled(Tiling::Spiral, 5)), "h(1,v(2,h(v(5,4),3)))");
+    }
+
+    #[test]
+    fn grid_rows() {
+        assert_eq!(shape(&tiled(Tiling::Grid, 4)), "v(h(1,2),h(3,4))");
+        assert_eq!(shape(&tiled(Tiling::Grid, 5)), "v(h(1,2,3),h(4,5))");
+        assert_eq!(shape(&tiled(Tiling::Grid, 0)), "");
+    }
+
+    #[test]
+    fn monocle_and_centered_master() {
+        assert_eq!(shape(&tiled(Tiling::Monocle, 3)), "tabs(1,2,3)");
+
+        let algorithm = Tiling::CenteredMaster { ratio: 50 };
+        assert_eq!(shape(&tiled(algorithm.clone(), 2)), "h(1,2)");
+        assert_eq!(shape(&tiled(algorithm, 5)), "h(v(3,5),1,v(2,4))");
+    }
+
+    #[test]
+    fn components_are_added_and_removed() {
+        let mut tiled = Tiled::new(Tiling::Dwindle, vec![component(1)], None);
+        tiled.add(component(2), None);
+        tiled.add(component(3), Some(0));
+        tiled.add(component(4), Some(10));
+        assert!(tiled.components == vec![component(3), component(1), component(2), component(4)]);
+
+        assert!(tiled.remove(&component(1)).is_ok());
+        assert!(tiled.remove(&component(1)) == Err(LayoutError::NotFound));
+        assert!(tiled.components == vec![component(3), component(2), component(4)]);
+    }
+}